@@ -1,21 +1,53 @@
-use crate::constants::{
-    BIN_NAME, BUILD_TIMESTAMP_UTC, LAST_COMMIT_DATE, LAST_COMMIT_ID, LAST_COMMIT_ID_LONG, VERSION,
+use crate::{
+    compare::{self, CompareOptions},
+    constants::{
+        BIN_NAME, BUILD_TIMESTAMP_UTC, LAST_COMMIT_DATE, LAST_COMMIT_ID, LAST_COMMIT_ID_LONG,
+        VERSION,
+    },
+    output::OutputFormat,
+    registers,
 };
 use clap::{ArgAction, ArgGroup, arg, builder::ValueParser, command, value_parser};
 use std::{
     collections::HashMap,
+    ops::RangeInclusive,
     path::{Path, PathBuf},
 };
 
 #[derive(Debug)]
 pub(crate) struct Args {
+    command: Command,
     input: Input,
     registers: HashMap<usize, u64>,
+    resume: Option<PathBuf>,
+    pause_after: Option<u64>,
+    snapshot: Option<PathBuf>,
+    output_format: OutputFormat,
 }
 
 impl Args {
-    pub(crate) fn new(input: Input, registers: HashMap<usize, u64>) -> Self {
-        Self { input, registers }
+    pub(crate) fn new(
+        command: Command,
+        input: Input,
+        registers: HashMap<usize, u64>,
+        resume: Option<PathBuf>,
+        pause_after: Option<u64>,
+        snapshot: Option<PathBuf>,
+        output_format: OutputFormat,
+    ) -> Self {
+        Self {
+            command,
+            input,
+            registers,
+            resume,
+            pause_after,
+            snapshot,
+            output_format,
+        }
+    }
+
+    pub(crate) fn command(&self) -> &Command {
+        &self.command
     }
 
     pub(crate) fn input(&self) -> &Input {
@@ -25,6 +57,57 @@ impl Args {
     pub(crate) fn registers(&self) -> &HashMap<usize, u64> {
         &self.registers
     }
+
+    pub(crate) fn resume(&self) -> Option<&PathBuf> {
+        self.resume.as_ref()
+    }
+
+    pub(crate) fn pause_after(&self) -> Option<u64> {
+        self.pause_after
+    }
+
+    pub(crate) fn snapshot(&self) -> Option<&PathBuf> {
+        self.snapshot.as_ref()
+    }
+
+    pub(crate) fn output_format(&self) -> OutputFormat {
+        self.output_format
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Command {
+    Run,
+    Check,
+    Trace,
+    Fmt {
+        output: Option<PathBuf>,
+        check: bool,
+    },
+    Analyze,
+    Test {
+        cases: Option<PathBuf>,
+        max_steps: u64,
+    },
+    Compare {
+        reference: Input,
+        candidate: Input,
+        options: CompareOptions,
+    },
+    Grade {
+        spec: PathBuf,
+        submissions: Vec<PathBuf>,
+        report: Option<PathBuf>,
+    },
+    Build {
+        output: PathBuf,
+        strip_debug_info: bool,
+    },
+    Optimize {
+        output: Option<PathBuf>,
+    },
+    Debug,
+    Tui,
 }
 
 #[derive(Clone, Debug)]
@@ -64,14 +147,154 @@ pub(crate) fn parse_args_or_exit() -> Result<Args, clap::Error> {
             "license",
             "build-info",
         ]))
-        // input file
-        .arg(
-            arg!([INPUT] "Input file path, raw string, or omit to read from stdin")
-                .action(ArgAction::Set)
-                .value_parser(ValueParser::new(parse_input)),
+        // `arams <INPUT>` is a shorthand for `arams run <INPUT>`
+        .args(run_args())
+        .group(ArgGroup::new("run-args").multiple(true).args(["INPUT"]))
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            clap::Command::new("run")
+                .about("Compile and execute a program")
+                .args(run_args()),
+        )
+        .subcommand(
+            clap::Command::new("check")
+                .about("Compile a program without executing it and report all errors")
+                .arg(input_arg())
+                .arg(output_format_arg()),
+        )
+        .subcommand(
+            clap::Command::new("trace")
+                .about("Execute a program and print every executed instruction")
+                .arg(input_arg())
+                .args(registers_args())
+                .arg(output_format_arg()),
+        )
+        .subcommand(
+            clap::Command::new("fmt")
+                .about("Format the source code of a program")
+                .arg(input_arg())
+                .arg(
+                    arg!(-o --output <FILE> "Write the formatted source code to a file instead of stdout")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(--check "Only check whether the program is formatted, fail if it is not")
+                        .action(ArgAction::SetTrue)
+                        .value_parser(value_parser!(bool))
+                        .conflicts_with("output"),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("analyze")
+                .about("Print the tokens of a program together with their errors")
+                .arg(input_arg()),
+        )
+        .subcommand(
+            clap::Command::new("test")
+                .about("Run the test cases declared with `// @test` directives or in a TOML file")
+                .arg(input_arg())
+                .arg(
+                    arg!(--cases <FILE> "TOML file with additional test cases (default: the input file with a .toml extension, if it exists)")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(--"max-steps" <STEPS> "Fail test cases that do not stop within the given number of steps, unless they declare their own bound")
+                        .value_parser(value_parser!(u64))
+                        .default_value("1000000"),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("compare")
+                .about("Check whether a program computes the same outputs as a reference solution")
+                .arg(
+                    arg!(<REFERENCE> "Reference solution, a file path or raw string")
+                        .value_parser(ValueParser::new(parse_input)),
+                )
+                .arg(
+                    arg!(<CANDIDATE> "Program to compare, a file path or raw string")
+                        .value_parser(ValueParser::new(parse_input)),
+                )
+                .arg(
+                    arg!(-i --inputs <RANGES> "Values of the input registers, can be repeated (format: r1=0..=10,r2=0..5)")
+                        .action(ArgAction::Append)
+                        .value_parser(|input: &str| registers::parse_value_ranges(input)),
+                )
+                .arg(
+                    arg!(--samples <COUNT> "Compare a random sample of inputs instead of all of them")
+                        .value_parser(value_parser!(u64)),
+                )
+                .arg(
+                    arg!(--seed <SEED> "Seed of the random sample")
+                        .value_parser(value_parser!(u64))
+                        .default_value("0")
+                        .requires("samples"),
+                )
+                .arg(
+                    arg!(--outputs <OUTPUTS> "Values that have to match (format: acc,r2)")
+                        .value_parser(compare::parse_outputs)
+                        .default_value("acc"),
+                )
+                .arg(
+                    arg!(--"max-steps" <STEPS> "Treat executions that do not stop within the given number of steps as non-terminating")
+                        .value_parser(value_parser!(u64))
+                        .default_value("1000000"),
+                )
+                .arg(output_format_arg()),
+        )
+        .subcommand(
+            clap::Command::new("grade")
+                .about("Grade a directory of submissions against the test cases of an exercise")
+                .arg(
+                    arg!(--spec <FILE> "TOML file with the test cases, step limit and register contract of the exercise")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(<SUBMISSIONS> ... "Submitted files or directories containing them")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(--report <FILE> "Write the report to a .csv or .json file")
+                        .value_parser(parse_report_path),
+                )
+                .arg(output_format_arg()),
+        )
+        .subcommand(
+            clap::Command::new("build")
+                .about("Compile a program into the binary bytecode format")
+                .arg(input_arg())
+                .arg(
+                    arg!(-o --output <FILE> "Output file path of the compiled program, e.g. prog.ramc")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(--strip "Omit the label and source line tables from the compiled program")
+                        .action(ArgAction::SetTrue)
+                        .value_parser(value_parser!(bool)),
+                ),
         )
-        .arg(arg!(-r --registers <VALUES> "Preseed the registers of the simulated machine (format: [(1,2),(2,4)], default: all registers at 0)").action(ArgAction::Set).value_parser(parse_register_preseed))
-        .group(ArgGroup::new("run-args").multiple(true).args(["INPUT"]));
+        .subcommand(
+            clap::Command::new("optimize")
+                .about("Optimize a program and report what has changed")
+                .arg(input_arg())
+                .arg(
+                    arg!(-o --output <FILE> "Write the optimized source code to a file instead of stdout")
+                        .value_parser(value_parser!(PathBuf)),
+                ),
+        )
+        .subcommand(
+            clap::Command::new("debug")
+                .about("Step through a program in an interactive debugger")
+                .arg(input_arg().required(true))
+                .args(registers_args()),
+        )
+        .subcommand(
+            clap::Command::new("tui")
+                .about("Watch a program execute in a full-screen terminal user interface")
+                .arg(input_arg().required(true))
+                .args(registers_args()),
+        );
 
     let matches = command.get_matches();
 
@@ -86,17 +309,194 @@ pub(crate) fn parse_args_or_exit() -> Result<Args, clap::Error> {
         std::process::exit(0);
     }
 
-    let input = matches
-        .get_one::<Input>("INPUT")
+    let (command, input_matches) = match matches.subcommand() {
+        Some(("run", run_matches)) => (Command::Run, run_matches),
+        Some(("check", check_matches)) => (Command::Check, check_matches),
+        Some(("trace", trace_matches)) => (Command::Trace, trace_matches),
+        Some(("fmt", fmt_matches)) => (
+            Command::Fmt {
+                output: fmt_matches.get_one::<PathBuf>("output").cloned(),
+                check: fmt_matches.get_flag("check"),
+            },
+            fmt_matches,
+        ),
+        Some(("analyze", analyze_matches)) => (Command::Analyze, analyze_matches),
+        Some(("test", test_matches)) => (
+            Command::Test {
+                cases: test_matches.get_one::<PathBuf>("cases").cloned(),
+                max_steps: test_matches
+                    .get_one::<u64>("max-steps")
+                    .copied()
+                    .expect("max-steps has a default value"),
+            },
+            test_matches,
+        ),
+        Some(("compare", compare_matches)) => (
+            Command::Compare {
+                reference: compare_matches
+                    .get_one::<Input>("REFERENCE")
+                    .cloned()
+                    .expect("reference is required"),
+                candidate: compare_matches
+                    .get_one::<Input>("CANDIDATE")
+                    .cloned()
+                    .expect("candidate is required"),
+                options: CompareOptions::new(
+                    compare_matches
+                        .get_many::<Vec<(usize, RangeInclusive<u64>)>>("inputs")
+                        .map(|inputs| inputs.flatten().cloned().collect())
+                        .unwrap_or_default(),
+                    compare_matches.get_one::<u64>("samples").copied(),
+                    compare_matches
+                        .get_one::<u64>("seed")
+                        .copied()
+                        .expect("seed has a default value"),
+                    compare_matches
+                        .get_one::<u64>("max-steps")
+                        .copied()
+                        .expect("max-steps has a default value"),
+                    compare_matches
+                        .get_one::<Vec<Option<usize>>>("outputs")
+                        .cloned()
+                        .expect("outputs has a default value"),
+                ),
+            },
+            compare_matches,
+        ),
+        Some(("grade", grade_matches)) => (
+            Command::Grade {
+                spec: grade_matches
+                    .get_one::<PathBuf>("spec")
+                    .cloned()
+                    .expect("spec is required"),
+                submissions: grade_matches
+                    .get_many::<PathBuf>("SUBMISSIONS")
+                    .expect("submissions are required")
+                    .cloned()
+                    .collect(),
+                report: grade_matches.get_one::<PathBuf>("report").cloned(),
+            },
+            grade_matches,
+        ),
+        Some(("build", build_matches)) => (
+            Command::Build {
+                output: build_matches
+                    .get_one::<PathBuf>("output")
+                    .cloned()
+                    .expect("output is required"),
+                strip_debug_info: build_matches.get_flag("strip"),
+            },
+            build_matches,
+        ),
+        Some(("optimize", optimize_matches)) => (
+            Command::Optimize {
+                output: optimize_matches.get_one::<PathBuf>("output").cloned(),
+            },
+            optimize_matches,
+        ),
+        Some(("debug", debug_matches)) => (Command::Debug, debug_matches),
+        Some(("tui", tui_matches)) => (Command::Tui, tui_matches),
+        _ => (Command::Run, &matches),
+    };
+
+    let input = input_matches
+        .try_get_one::<Input>("INPUT")
+        .ok()
+        .flatten()
         .cloned()
         .unwrap_or(Input::None);
 
-    let registers = matches
-        .get_one::<HashMap<usize, u64>>("registers")
+    // assignments override the registers file, arrays override both
+    let mut registers = input_matches
+        .try_get_one::<HashMap<usize, u64>>("registers-file")
+        .ok()
+        .flatten()
         .cloned()
-        .unwrap_or_else(HashMap::new);
+        .unwrap_or_default();
+    for id in ["registers", "array"] {
+        if let Ok(Some(assignments)) = input_matches.try_get_many::<Vec<(usize, u64)>>(id) {
+            registers.extend(assignments.flatten().copied());
+        }
+    }
+
+    let resume = input_matches
+        .try_get_one::<PathBuf>("resume")
+        .ok()
+        .flatten()
+        .cloned();
+    let pause_after = input_matches
+        .try_get_one::<u64>("pause-after")
+        .ok()
+        .flatten()
+        .copied();
+    let snapshot = input_matches
+        .try_get_one::<PathBuf>("snapshot")
+        .ok()
+        .flatten()
+        .cloned();
+    let output_format = input_matches
+        .try_get_one::<OutputFormat>("format")
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or_default();
+
+    Ok(Args::new(
+        command,
+        input,
+        registers,
+        resume,
+        pause_after,
+        snapshot,
+        output_format,
+    ))
+}
+
+// shared by `arams run` and the `arams <INPUT>` shorthand
+fn run_args() -> Vec<clap::Arg> {
+    let mut args = vec![input_arg()];
+    args.extend(registers_args());
+    args.extend([
+        arg!(--resume <SNAPSHOT> "Resume a paused execution from a snapshot file instead of compiling an input")
+            .action(ArgAction::Set)
+            .value_parser(value_parser!(PathBuf))
+            .conflicts_with_all(["INPUT", "registers", "registers-file", "array"]),
+        arg!(--"pause-after" <STEPS> "Pause the execution after the given number of steps and save a snapshot")
+            .action(ArgAction::Set)
+            .value_parser(value_parser!(u64)),
+        arg!(--snapshot <FILE> "File to save the snapshot of a paused execution to (default: stdout)")
+            .action(ArgAction::Set)
+            .value_parser(value_parser!(PathBuf))
+            .requires("pause-after"),
+        output_format_arg(),
+    ]);
+    args
+}
+
+fn output_format_arg() -> clap::Arg {
+    arg!(--format <FORMAT> "Format of results and errors: json, csv, table or plain (default: plain)")
+        .action(ArgAction::Set)
+        .value_parser(|format: &str| format.parse::<OutputFormat>())
+}
 
-    Ok(Args::new(input, registers))
+fn input_arg() -> clap::Arg {
+    arg!([INPUT] "Input file path, raw string, or omit to read from stdin")
+        .action(ArgAction::Set)
+        .value_parser(ValueParser::new(parse_input))
+}
+
+fn registers_args() -> [clap::Arg; 3] {
+    [
+        arg!(-r --registers <VALUES> "Preseed registers, can be repeated (format: 1=5, 10..=20=0 or [(1,2),(2,4)], default: all registers at 0)")
+            .action(ArgAction::Append)
+            .value_parser(|input: &str| registers::parse_assignments(input)),
+        arg!(--"registers-file" <FILE> "Preseed registers from a JSON, TOML or CSV file")
+            .action(ArgAction::Set)
+            .value_parser(parse_registers_file),
+        arg!(--array <VALUES> "Preseed consecutive registers starting at a base register, can be repeated (format: 10:3,1,4,1,5)")
+            .action(ArgAction::Append)
+            .value_parser(|input: &str| registers::parse_array(input)),
+    ]
 }
 
 fn parse_input(input_str: &str) -> Result<Input, clap::Error> {
@@ -110,48 +510,22 @@ fn parse_input(input_str: &str) -> Result<Input, clap::Error> {
     }
 }
 
-fn parse_register_preseed(input_str: &str) -> Result<HashMap<usize, u64>, clap::Error> {
-    let trimmed = input_str.trim();
-    if trimmed.is_empty() {
-        return Ok(HashMap::new());
-    }
-
-    let pairs: Vec<&str> = trimmed
-        .trim_start_matches('[')
-        .trim_end_matches(']')
-        .split("),(")
-        .collect();
-
-    let mut register_map = HashMap::new();
+fn parse_registers_file(path: &str) -> Result<HashMap<usize, u64>, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
 
-    for pair in pairs {
-        let clean_pair = pair.trim_matches(|c| c == '(' || c == ')');
-        let nums: Vec<&str> = clean_pair.split(',').collect();
-        if nums.len() != 2 {
-            return Err(clap::Error::raw(
-                clap::error::ErrorKind::InvalidValue,
-                format!("Invalid register pair: {}", pair),
-            ));
-        }
-
-        let reg_index: usize = nums[0].trim().parse().map_err(|e| {
-            clap::Error::raw(
-                clap::error::ErrorKind::InvalidValue,
-                format!("Invalid register index in pair {}: {}", pair, e),
-            )
-        })?;
-
-        let reg_value: u64 = nums[1].trim().parse().map_err(|e| {
-            clap::Error::raw(
-                clap::error::ErrorKind::InvalidValue,
-                format!("Invalid register value in pair {}: {}", pair, e),
-            )
-        })?;
-
-        register_map.insert(reg_index, reg_value);
+    match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("json") => registers::parse_json(&contents),
+        Some("toml") => registers::parse_toml(&contents),
+        Some("csv") => registers::parse_csv(&contents),
+        _ => Err("Unknown file format, expected a .json, .toml or .csv file".to_string()),
     }
+}
 
-    Ok(register_map)
+fn parse_report_path(path: &str) -> Result<PathBuf, String> {
+    match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("csv") | Some("json") => Ok(PathBuf::from(path)),
+        _ => Err("Unknown report format, expected a .csv or .json file".to_string()),
+    }
 }
 
 fn print_license() {
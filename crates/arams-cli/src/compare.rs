@@ -0,0 +1,498 @@
+use arams_core::{Machine, Program, step};
+use serde_json::{Value, json};
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use crate::{errors::Error, output::OutputFormat};
+
+// larger input spaces have to be sampled with `--samples`
+const MAX_EXHAUSTIVE_INPUTS: u64 = 1_000_000;
+
+#[derive(Clone, Debug)]
+pub(crate) struct CompareOptions {
+    inputs: Vec<(usize, RangeInclusive<u64>)>,
+    samples: Option<u64>,
+    seed: u64,
+    max_steps: u64,
+    // `None` is the accumulator
+    outputs: Vec<Option<usize>>,
+}
+
+impl CompareOptions {
+    pub(crate) fn new(
+        inputs: Vec<(usize, RangeInclusive<u64>)>,
+        samples: Option<u64>,
+        seed: u64,
+        max_steps: u64,
+        outputs: Vec<Option<usize>>,
+    ) -> Self {
+        Self {
+            inputs,
+            samples,
+            seed,
+            max_steps,
+            outputs,
+        }
+    }
+}
+
+// `acc,r2` compares the accumulator and register 2
+pub(crate) fn parse_outputs(input: &str) -> Result<Vec<Option<usize>>, String> {
+    input
+        .split(',')
+        .map(|output| match output.trim() {
+            "acc" | "accumulator" => Ok(None),
+            register => crate::registers::parse_key(register).map(Some),
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+enum Outcome {
+    Stopped { values: Vec<u64>, steps: u64 },
+    DidNotStop { max_steps: u64 },
+    RuntimeError(String),
+}
+
+impl Outcome {
+    // step counts and error messages may differ between equivalent programs
+    fn same_result(&self, other: &Outcome) -> bool {
+        match (self, other) {
+            (Outcome::Stopped { values, .. }, Outcome::Stopped { values: other, .. }) => {
+                values == other
+            }
+            (Outcome::DidNotStop { .. }, Outcome::DidNotStop { .. }) => true,
+            (Outcome::RuntimeError(_), Outcome::RuntimeError(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn to_string(&self, outputs: &[Option<usize>]) -> String {
+        match self {
+            Outcome::Stopped { values, steps } => {
+                let values: Vec<String> = outputs
+                    .iter()
+                    .zip(values)
+                    .map(|(output, value)| format!("{} = {}", output_name(output), value))
+                    .collect();
+                format!("{} ({} steps)", values.join(", "), steps)
+            }
+            Outcome::DidNotStop { max_steps } => {
+                format!("did not stop within {} steps", max_steps)
+            }
+            Outcome::RuntimeError(err) => format!("runtime error: {}", err),
+        }
+    }
+
+    fn to_json(&self, outputs: &[Option<usize>]) -> Value {
+        match self {
+            Outcome::Stopped { values, steps } => {
+                let values: serde_json::Map<String, Value> = outputs
+                    .iter()
+                    .zip(values)
+                    .map(|(output, value)| (output_name(output), json!(value)))
+                    .collect();
+                json!({ "status": "stopped", "outputs": values, "steps": steps })
+            }
+            Outcome::DidNotStop { max_steps } => {
+                json!({ "status": "did_not_stop", "max_steps": max_steps })
+            }
+            Outcome::RuntimeError(err) => json!({ "status": "runtime_error", "message": err }),
+        }
+    }
+}
+
+#[derive(Default)]
+struct StepStatistics {
+    min: Option<u64>,
+    max: u64,
+    total: u128,
+    count: u64,
+}
+
+impl StepStatistics {
+    fn record(&mut self, outcome: &Outcome) {
+        if let Outcome::Stopped { steps, .. } = outcome {
+            self.min = Some(self.min.map_or(*steps, |min| min.min(*steps)));
+            self.max = self.max.max(*steps);
+            self.total += *steps as u128;
+            self.count += 1;
+        }
+    }
+
+    fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.total as f64 / self.count as f64)
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "min": self.min,
+            "mean": self.mean(),
+            "max": self.min.map(|_| self.max),
+        })
+    }
+}
+
+struct Difference {
+    inputs: Vec<(usize, u64)>,
+    reference: Outcome,
+    candidate: Outcome,
+}
+
+pub(crate) struct Comparison {
+    outputs: Vec<Option<usize>>,
+    // `None` if the input space has been enumerated exhaustively
+    seed: Option<u64>,
+    compared: u64,
+    differing: u64,
+    first_difference: Option<Difference>,
+    reference_steps: StepStatistics,
+    candidate_steps: StepStatistics,
+}
+
+impl Comparison {
+    pub(crate) fn differing(&self) -> u64 {
+        self.differing
+    }
+
+    pub(crate) fn compared(&self) -> u64 {
+        self.compared
+    }
+}
+
+// both programs run without their own `// @input` defaults, only the input space decides the registers
+pub(crate) fn compare(
+    reference: &Program,
+    candidate: &Program,
+    options: &CompareOptions,
+) -> Result<Comparison, Error> {
+    let reference = reference.clone().without_directives();
+    let candidate = candidate.clone().without_directives();
+
+    let sizes: Vec<u64> = options
+        .inputs
+        .iter()
+        .map(|(_, range)| range.end().saturating_sub(*range.start()).saturating_add(1))
+        .collect();
+    let count = match options.samples {
+        Some(samples) => samples,
+        None => sizes
+            .iter()
+            .try_fold(1u64, |count, size| count.checked_mul(*size))
+            .filter(|count| *count <= MAX_EXHAUSTIVE_INPUTS)
+            .ok_or(Error::InputSpaceTooLarge {
+                limit: MAX_EXHAUSTIVE_INPUTS,
+            })?,
+    };
+
+    let mut comparison = Comparison {
+        outputs: options.outputs.clone(),
+        seed: options.samples.map(|_| options.seed),
+        compared: 0,
+        differing: 0,
+        first_difference: None,
+        reference_steps: StepStatistics::default(),
+        candidate_steps: StepStatistics::default(),
+    };
+
+    let mut rng = Rng::new(options.seed);
+    for index in 0..count {
+        let inputs: Vec<(usize, u64)> = match options.samples {
+            Some(_) => options
+                .inputs
+                .iter()
+                .map(|(register, range)| (*register, rng.next_in(range)))
+                .collect(),
+            None => exhaustive_inputs(&options.inputs, &sizes, index),
+        };
+
+        let registers: HashMap<usize, u64> = inputs.iter().copied().collect();
+        let reference_outcome = run(&reference, &registers, options);
+        let candidate_outcome = run(&candidate, &registers, options);
+
+        comparison.compared += 1;
+        comparison.reference_steps.record(&reference_outcome);
+        comparison.candidate_steps.record(&candidate_outcome);
+        if !reference_outcome.same_result(&candidate_outcome) {
+            comparison.differing += 1;
+            if comparison.first_difference.is_none() {
+                comparison.first_difference = Some(Difference {
+                    inputs,
+                    reference: reference_outcome,
+                    candidate: candidate_outcome,
+                });
+            }
+        }
+    }
+
+    Ok(comparison)
+}
+
+// the index is split into one digit per register, the first register changes slowest
+fn exhaustive_inputs(
+    inputs: &[(usize, RangeInclusive<u64>)],
+    sizes: &[u64],
+    index: u64,
+) -> Vec<(usize, u64)> {
+    let mut rest = index;
+    let mut inputs: Vec<(usize, u64)> = inputs
+        .iter()
+        .zip(sizes)
+        .rev()
+        .map(|((register, range), size)| {
+            let value = range.start() + rest % size;
+            rest /= size;
+            (*register, value)
+        })
+        .collect();
+    inputs.reverse();
+    inputs
+}
+
+fn run(program: &Program, registers: &HashMap<usize, u64>, options: &CompareOptions) -> Outcome {
+    let mut machine = Machine::new_with_preseeded_registers(registers.clone());
+
+    while !machine.is_stopped() {
+        if machine.get_steps() >= options.max_steps {
+            return Outcome::DidNotStop {
+                max_steps: options.max_steps,
+            };
+        }
+        if let Err(err) = step(program, &mut machine) {
+            return Outcome::RuntimeError(err.to_string());
+        }
+    }
+
+    Outcome::Stopped {
+        values: options
+            .outputs
+            .iter()
+            .map(|output| match output {
+                Some(register) => *machine.get_register_value(*register),
+                None => machine.get_accumulator(),
+            })
+            .collect(),
+        steps: machine.get_steps(),
+    }
+}
+
+pub(crate) fn print_comparison(format: OutputFormat, comparison: &Comparison) {
+    let mode = match comparison.seed {
+        Some(seed) => format!("random, seed {}", seed),
+        None => "exhaustive".to_string(),
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let first_difference = comparison.first_difference.as_ref().map(|difference| {
+                let inputs: serde_json::Map<String, Value> = difference
+                    .inputs
+                    .iter()
+                    .map(|(register, value)| (register.to_string(), json!(value)))
+                    .collect();
+                json!({
+                    "inputs": inputs,
+                    "reference": difference.reference.to_json(&comparison.outputs),
+                    "candidate": difference.candidate.to_json(&comparison.outputs),
+                })
+            });
+            println!(
+                "{}",
+                json!({
+                    "status": if comparison.differing == 0 { "equivalent" } else { "different" },
+                    "mode": if comparison.seed.is_some() { "random" } else { "exhaustive" },
+                    "seed": comparison.seed,
+                    "compared": comparison.compared,
+                    "differing": comparison.differing,
+                    "first_difference": first_difference,
+                    "steps": {
+                        "reference": comparison.reference_steps.to_json(),
+                        "candidate": comparison.candidate_steps.to_json(),
+                    },
+                })
+            );
+        }
+        OutputFormat::Csv | OutputFormat::Table | OutputFormat::Plain => {
+            match &comparison.first_difference {
+                None => println!(
+                    "compared {} inputs ({}), no differences",
+                    comparison.compared, mode
+                ),
+                Some(difference) => {
+                    println!(
+                        "compared {} inputs ({}), {} differ",
+                        comparison.compared, mode, comparison.differing
+                    );
+                    let inputs: Vec<String> = difference
+                        .inputs
+                        .iter()
+                        .map(|(register, value)| format!("r{} = {}", register, value))
+                        .collect();
+                    println!("first difference at {}:", inputs.join(", "));
+                    println!(
+                        "    reference: {}",
+                        difference.reference.to_string(&comparison.outputs)
+                    );
+                    println!(
+                        "    candidate: {}",
+                        difference.candidate.to_string(&comparison.outputs)
+                    );
+                }
+            }
+
+            println!();
+            println!("{:<10} {:>10} {:>12} {:>10}", "steps", "min", "mean", "max");
+            for (name, statistics) in [
+                ("reference", &comparison.reference_steps),
+                ("candidate", &comparison.candidate_steps),
+            ] {
+                match (statistics.min, statistics.mean()) {
+                    (Some(min), Some(mean)) => println!(
+                        "{:<10} {:>10} {:>12.1} {:>10}",
+                        name, min, mean, statistics.max
+                    ),
+                    _ => println!("{:<10} {:>10} {:>12} {:>10}", name, "-", "-", "-"),
+                }
+            }
+        }
+    }
+}
+
+fn output_name(output: &Option<usize>) -> String {
+    match output {
+        Some(register) => format!("r{}", register),
+        None => "acc".to_string(),
+    }
+}
+
+// SplitMix64, the same seed samples the same inputs on every platform
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // the modulo bias is negligible for the small ranges inputs are drawn from
+    fn next_in(&mut self, range: &RangeInclusive<u64>) -> u64 {
+        match range.end() - range.start() {
+            u64::MAX => self.next(),
+            span => range.start() + self.next() % (span + 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arams_core::compile;
+
+    fn options(inputs: Vec<(usize, RangeInclusive<u64>)>, samples: Option<u64>) -> CompareOptions {
+        CompareOptions::new(inputs, samples, 42, 1_000, vec![None])
+    }
+
+    fn stopped(values: Vec<u64>, steps: u64) -> Outcome {
+        Outcome::Stopped { values, steps }
+    }
+
+    #[test]
+    fn exhaustive_inputs_count_like_mixed_radix_numbers() {
+        let inputs = vec![(1, 0..=1), (2, 5..=7)];
+        let sizes = [2, 3];
+
+        let enumerated: Vec<Vec<(usize, u64)>> = (0..6)
+            .map(|index| exhaustive_inputs(&inputs, &sizes, index))
+            .collect();
+
+        assert_eq!(
+            enumerated,
+            [
+                [(1, 0), (2, 5)],
+                [(1, 0), (2, 6)],
+                [(1, 0), (2, 7)],
+                [(1, 1), (2, 5)],
+                [(1, 1), (2, 6)],
+                [(1, 1), (2, 7)],
+            ]
+        );
+    }
+
+    #[test]
+    fn exhaustive_comparison_covers_every_input() {
+        let reference = compile("load 1\nadd 2\nend").unwrap();
+        let candidate = compile("load 2\nadd 1\nend").unwrap();
+        let inputs = vec![(1, 0..=3), (2, 10..=14)];
+
+        let comparison = compare(&reference, &candidate, &options(inputs.clone(), None)).unwrap();
+        assert_eq!((comparison.compared, comparison.differing), (20, 0));
+        assert_eq!(comparison.seed, None);
+
+        let candidate = compile("load 2\nend").unwrap();
+        let comparison = compare(&reference, &candidate, &options(inputs, None)).unwrap();
+        assert_eq!((comparison.compared, comparison.differing), (20, 15));
+        let difference = comparison.first_difference.unwrap();
+        assert_eq!(difference.inputs, [(1, 1), (2, 10)]);
+    }
+
+    #[test]
+    fn large_input_spaces_have_to_be_sampled() {
+        let reference = compile("end").unwrap();
+        let too_large = vec![(1, 0..=1000), (2, 1..=1000)];
+        let overflowing = vec![(1, 0..=u64::MAX), (2, 0..=u64::MAX)];
+
+        for inputs in [too_large, overflowing.clone()] {
+            assert!(matches!(
+                compare(&reference, &reference, &options(inputs, None)),
+                Err(Error::InputSpaceTooLarge {
+                    limit: MAX_EXHAUSTIVE_INPUTS
+                })
+            ));
+        }
+
+        let comparison = compare(&reference, &reference, &options(overflowing, Some(10))).unwrap();
+        assert_eq!(comparison.compared, 10);
+        assert_eq!(comparison.seed, Some(42));
+    }
+
+    #[test]
+    fn rng_is_reproducible() {
+        // the first output of SplitMix64 seeded with 0
+        assert_eq!(Rng::new(0).next(), 0xE220_A839_7B1D_CDAF);
+
+        let sequence = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..100)
+                .map(|_| rng.next_in(&(10..=20)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sequence(7), sequence(7));
+        assert_ne!(sequence(7), sequence(8));
+        assert!(sequence(7).iter().all(|value| (10..=20).contains(value)));
+
+        let mut rng = Rng::new(7);
+        assert_eq!(rng.next_in(&(5..=5)), 5);
+        let mut full = Rng::new(7);
+        assert_eq!(full.next_in(&(0..=u64::MAX)), Rng::new(7).next());
+    }
+
+    #[test]
+    fn same_result_ignores_steps_and_messages() {
+        let did_not_stop = |max_steps| Outcome::DidNotStop { max_steps };
+        let error = |message: &str| Outcome::RuntimeError(message.to_string());
+
+        assert!(stopped(vec![1, 2], 5).same_result(&stopped(vec![1, 2], 9)));
+        assert!(!stopped(vec![1, 2], 5).same_result(&stopped(vec![1, 3], 5)));
+        assert!(did_not_stop(10).same_result(&did_not_stop(20)));
+        assert!(error("division by zero").same_result(&error("unknown label")));
+        assert!(!stopped(vec![0], 1).same_result(&did_not_stop(1)));
+        assert!(!error("division by zero").same_result(&did_not_stop(1)));
+        assert!(!did_not_stop(1).same_result(&stopped(vec![0], 1)));
+    }
+}
@@ -0,0 +1,502 @@
+use arams_core::{Machine, Program, step};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io::{BufRead, Write},
+};
+
+use crate::errors::Error;
+
+const PROMPT: &str = "(arams) ";
+const LIST_CONTEXT: usize = 5;
+// a command pauses after this many steps, so `continue` on an endless loop does not hang the debugger
+const RUN_LIMIT: u64 = 100_000;
+
+const HELP: &str = "Commands:
+  step, s [COUNT]          Execute the next instruction(s)
+  next, n                  Execute until the instruction after the current one is reached (steps over loops)
+  continue, c              Execute until a breakpoint, a watched register changes or the program ends
+                           (every command pauses after 100000 steps)
+  break, b [LABEL|LINE]    Set a breakpoint or list all breakpoints
+  delete, d <LABEL|LINE>   Remove a breakpoint
+  print, p <acc|pc|rN|*N|registers>
+                           Print the accumulator, program counter, a register or all registers
+  set <acc|rN> <VALUE>     Change the accumulator or a register
+  watch, w <rN>            Stop whenever the register changes
+  unwatch <rN>             Stop watching a register
+  list, l [LINE]           Show the source code around the current or the given line
+  trace <on|off>           Print every executed instruction
+  reset                    Restart the program with the initial registers
+  help, h                  Show this help
+  quit, q                  Exit the debugger";
+
+pub(crate) fn debug(
+    program: Program,
+    source: Vec<String>,
+    registers: HashMap<usize, u64>,
+) -> Result<(), Error> {
+    let mut debugger = Debugger::new(program, source, registers);
+    let stdin = std::io::stdin();
+
+    println!(
+        "Debugging program with {} instructions, type `help` for a list of commands.",
+        debugger.program.instructions().len()
+    );
+    debugger.print_location();
+
+    loop {
+        print!("{}", PROMPT);
+        // the prompt is purely cosmetic, a failed flush must not end the session
+        let _ = std::io::stdout().flush();
+
+        let mut line = String::new();
+        if stdin
+            .lock()
+            .read_line(&mut line)
+            .map_err(Error::IOFailedToReadFromStdIn)?
+            == 0
+        {
+            println!();
+            break;
+        }
+
+        if !debugger.execute_command(line.trim()) {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+struct Debugger {
+    program: Program,
+    source: Vec<String>,
+    initial_registers: HashMap<usize, u64>,
+    machine: Machine,
+    breakpoints: BTreeSet<usize>,
+    watches: BTreeMap<usize, u64>,
+    trace: bool,
+    steps: u64,
+}
+
+impl Debugger {
+    fn new(program: Program, source: Vec<String>, registers: HashMap<usize, u64>) -> Self {
+        Self {
+            program,
+            source,
+            machine: Machine::new_with_preseeded_registers(registers.clone()),
+            initial_registers: registers,
+            breakpoints: BTreeSet::new(),
+            watches: BTreeMap::new(),
+            trace: false,
+            steps: 0,
+        }
+    }
+
+    // returns false if the debugger should quit
+    fn execute_command(&mut self, command: &str) -> bool {
+        let words: Vec<&str> = command.split_whitespace().collect();
+
+        match words.as_slice() {
+            [] => {}
+            ["step" | "s"] => self.run(|_| true),
+            ["step" | "s", count] => match count.parse::<u64>() {
+                Ok(0) => {}
+                Ok(count) => {
+                    let mut remaining = count;
+                    self.run(|_| {
+                        remaining -= 1;
+                        remaining == 0
+                    })
+                }
+                Err(_) => println!("Invalid step count '{}'", count),
+            },
+            ["next" | "n"] => {
+                let next_instruction = self.machine.get_program_counter() + 1;
+                self.run(|debugger| debugger.machine.get_program_counter() >= next_instruction);
+            }
+            ["continue" | "c"] => self.run(|_| false),
+            ["break" | "b"] => self.print_breakpoints(),
+            ["break" | "b", target] => match self.resolve_target(target) {
+                Some(index) => {
+                    self.breakpoints.insert(index);
+                    println!("Breakpoint set at {}", self.describe(index));
+                }
+                None => println!("No instruction found for '{}'", target),
+            },
+            ["delete" | "d", target] => match self.resolve_target(target) {
+                Some(index) if self.breakpoints.remove(&index) => {
+                    println!("Breakpoint removed at {}", self.describe(index));
+                }
+                _ => println!("No breakpoint set at '{}'", target),
+            },
+            ["print" | "p", "registers"] => self.print_registers(),
+            ["print" | "p", "acc"] => println!("acc = {}", self.machine.get_accumulator()),
+            ["print" | "p", "pc"] => println!("pc = {}", self.machine.get_program_counter()),
+            ["print" | "p", register] if register.starts_with('*') => {
+                match register[1..].parse::<usize>() {
+                    Ok(pointer) => {
+                        let address = *self.machine.get_register_value(pointer) as usize;
+                        println!(
+                            "*{} = r{} = {}",
+                            pointer,
+                            address,
+                            self.machine.get_register_value(address)
+                        );
+                    }
+                    Err(_) => println!("Invalid register '{}'", register),
+                }
+            }
+            ["print" | "p", register] => match parse_register(register) {
+                Some(register) => println!(
+                    "r{} = {}",
+                    register,
+                    self.machine.get_register_value(register)
+                ),
+                None => println!("Invalid register '{}'", register),
+            },
+            ["set", target, value] => {
+                let Ok(value) = value.parse::<u64>() else {
+                    println!("Invalid value '{}'", value);
+                    return true;
+                };
+                let result = match *target {
+                    "acc" => self.machine.load(value),
+                    register => match parse_register(register) {
+                        Some(register) => self.machine.set_register_value(register, value),
+                        None => {
+                            println!("Invalid register '{}'", register);
+                            return true;
+                        }
+                    },
+                };
+                match result {
+                    Ok(()) => println!("{} = {}", target, value),
+                    Err(err) => println!("{}", err),
+                }
+            }
+            ["watch" | "w", register] => match parse_register(register) {
+                Some(register) => {
+                    let value = *self.machine.get_register_value(register);
+                    self.watches.insert(register, value);
+                    println!("Watching r{} (currently {})", register, value);
+                }
+                None => println!("Invalid register '{}'", register),
+            },
+            ["unwatch", register] => match parse_register(register) {
+                Some(register) if self.watches.remove(&register).is_some() => {
+                    println!("No longer watching r{}", register);
+                }
+                _ => println!("Register '{}' is not watched", register),
+            },
+            ["list" | "l"] => {
+                let line = self
+                    .program
+                    .source_line(self.machine.get_program_counter())
+                    .unwrap_or(self.source.len());
+                self.list(line);
+            }
+            ["list" | "l", line] => match line.parse::<usize>() {
+                Ok(line) => self.list(line),
+                Err(_) => println!("Invalid line '{}'", line),
+            },
+            ["trace", "on"] => {
+                self.trace = true;
+                println!("Tracing enabled");
+            }
+            ["trace", "off"] => {
+                self.trace = false;
+                println!("Tracing disabled");
+            }
+            ["reset"] => {
+                self.machine =
+                    Machine::new_with_preseeded_registers(self.initial_registers.clone());
+                self.steps = 0;
+                for (register, value) in self.watches.iter_mut() {
+                    *value = *self.machine.get_register_value(*register);
+                }
+                println!("Program restarted");
+                self.print_location();
+            }
+            ["help" | "h"] => println!("{}", HELP),
+            ["quit" | "q" | "exit"] => return false,
+            _ => println!(
+                "Unknown command '{}', type `help` for a list of commands",
+                command
+            ),
+        }
+
+        true
+    }
+
+    // executes instructions until `should_stop` returns true, a breakpoint is hit, a watched register changes,
+    // the machine stops or `RUN_LIMIT` steps have been executed
+    fn run(&mut self, mut should_stop: impl FnMut(&Self) -> bool) {
+        if self.machine.is_stopped() {
+            println!("The program is not running, use `reset` to start again");
+            return;
+        }
+
+        for executed in 1.. {
+            let program_counter = self.machine.get_program_counter();
+            if let Err(err) = step(&self.program, &mut self.machine) {
+                println!("{}", err);
+                return;
+            }
+            self.steps += 1;
+
+            if self.trace {
+                println!(
+                    "  {} (acc = {})",
+                    self.describe(program_counter),
+                    self.machine.get_accumulator()
+                );
+            }
+
+            if self.machine.is_stopped() {
+                println!(
+                    "Program stopped after {} steps with acc = {}",
+                    self.steps,
+                    self.machine.get_accumulator()
+                );
+                return;
+            }
+
+            let mut watch_triggered = false;
+            for (register, value) in self.watches.iter_mut() {
+                let new_value = *self.machine.get_register_value(*register);
+                if new_value != *value {
+                    println!("Watch r{}: {} -> {}", register, value, new_value);
+                    *value = new_value;
+                    watch_triggered = true;
+                }
+            }
+
+            let program_counter = self.machine.get_program_counter();
+            if self.breakpoints.contains(&program_counter) {
+                println!("Breakpoint hit");
+                break;
+            }
+            if watch_triggered || should_stop(self) {
+                break;
+            }
+            if executed == RUN_LIMIT {
+                println!("Paused after {} steps", RUN_LIMIT);
+                break;
+            }
+        }
+
+        self.print_location();
+    }
+
+    fn resolve_target(&self, target: &str) -> Option<usize> {
+        match target.parse::<usize>() {
+            Ok(line) => self
+                .program
+                .source_lines()
+                .iter()
+                .position(|&source_line| source_line >= line),
+            Err(_) => self
+                .program
+                .resolve_label(target)
+                .ok()
+                .filter(|&index| index < self.program.instructions().len()),
+        }
+    }
+
+    fn describe(&self, index: usize) -> String {
+        match (
+            self.program.source_line(index),
+            self.program.instructions().get(index),
+        ) {
+            (Some(line), Some(instruction)) => format!("line {}: {}", line, instruction),
+            (None, Some(instruction)) => format!("instruction {}: {}", index, instruction),
+            _ => "end of program".to_string(),
+        }
+    }
+
+    fn print_location(&self) {
+        if self.machine.is_stopped() {
+            println!("Program stopped");
+        } else {
+            println!("=> {}", self.describe(self.machine.get_program_counter()));
+        }
+    }
+
+    fn print_breakpoints(&self) {
+        if self.breakpoints.is_empty() {
+            println!("No breakpoints set");
+        }
+        for &index in &self.breakpoints {
+            println!("  {}", self.describe(index));
+        }
+    }
+
+    fn print_registers(&self) {
+        println!("acc = {}", self.machine.get_accumulator());
+        let mut registers: Vec<_> = self.machine.get_registers().iter().collect();
+        registers.sort_by_key(|(key, _)| *key);
+        for (key, value) in registers {
+            println!("r{} = {}", key, value);
+        }
+    }
+
+    fn list(&self, line: usize) {
+        let current_line = if self.machine.is_stopped() {
+            None
+        } else {
+            self.program.source_line(self.machine.get_program_counter())
+        };
+        let breakpoint_lines: BTreeSet<usize> = self
+            .breakpoints
+            .iter()
+            .filter_map(|&index| self.program.source_line(index))
+            .collect();
+
+        let first = line.saturating_sub(LIST_CONTEXT).max(1);
+        let last = (line + LIST_CONTEXT).min(self.source.len());
+        for line_number in first..=last {
+            let marker = match (
+                current_line == Some(line_number),
+                breakpoint_lines.contains(&line_number),
+            ) {
+                (true, _) => "=>",
+                (false, true) => " *",
+                (false, false) => "  ",
+            };
+            println!(
+                "{} {:>4} | {}",
+                marker,
+                line_number,
+                self.source[line_number - 1]
+            );
+        }
+    }
+}
+
+fn parse_register(register: &str) -> Option<usize> {
+    register.strip_prefix(['r', 'R'])?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTDOWN: &str = "load 1
+loop: sub #1
+store 1
+jnzero loop
+end";
+
+    fn debugger(source: &str, registers: &[(usize, u64)]) -> Debugger {
+        let program = arams_core::compile(source).unwrap();
+        let source = source.lines().map(str::to_string).collect();
+        Debugger::new(program, source, registers.iter().copied().collect())
+    }
+
+    fn execute(debugger: &mut Debugger, commands: &[&str]) {
+        for command in commands {
+            assert!(debugger.execute_command(command), "{}", command);
+        }
+    }
+
+    #[test]
+    fn step_executes_the_given_number_of_instructions() {
+        let mut debugger = debugger(COUNTDOWN, &[(1, 3)]);
+
+        execute(&mut debugger, &["step", "s 0", "step x"]);
+        assert_eq!(debugger.machine.get_program_counter(), 1);
+        assert_eq!(debugger.steps, 1);
+
+        execute(&mut debugger, &["step 3"]);
+        assert_eq!(debugger.machine.get_program_counter(), 1);
+        assert_eq!(debugger.machine.get_accumulator(), 2);
+        assert_eq!(debugger.steps, 4);
+    }
+
+    #[test]
+    fn next_steps_over_loops() {
+        let mut debugger = debugger(COUNTDOWN, &[(1, 3)]);
+
+        execute(&mut debugger, &["step 3", "next"]);
+        assert_eq!(debugger.machine.get_program_counter(), 4);
+        assert_eq!(debugger.machine.get_accumulator(), 0);
+        assert!(!debugger.machine.is_stopped());
+    }
+
+    #[test]
+    fn continue_runs_until_the_program_stops() {
+        let mut debugger = debugger(COUNTDOWN, &[(1, 3)]);
+
+        execute(&mut debugger, &["continue"]);
+        assert!(debugger.machine.is_stopped());
+        assert_eq!(debugger.machine.get_register_value(1), &0);
+
+        // a stopped program has to be reset first
+        execute(&mut debugger, &["c"]);
+        assert_eq!(debugger.steps, 11);
+        execute(&mut debugger, &["reset", "step"]);
+        assert_eq!(debugger.steps, 1);
+        assert_eq!(debugger.machine.get_accumulator(), 3);
+    }
+
+    #[test]
+    fn endless_loops_are_paused() {
+        let mut debugger = debugger("loop: goto loop\nend", &[]);
+
+        execute(&mut debugger, &["continue"]);
+        assert_eq!(debugger.steps, RUN_LIMIT);
+        assert!(!debugger.machine.is_stopped());
+
+        execute(&mut debugger, &["next"]);
+        assert_eq!(debugger.steps, 2 * RUN_LIMIT);
+    }
+
+    #[test]
+    fn breakpoints_pause_the_execution() {
+        let mut debugger = debugger(COUNTDOWN, &[(1, 3)]);
+
+        execute(
+            &mut debugger,
+            &["break loop", "b 3", "break nowhere", "b 99"],
+        );
+        assert_eq!(debugger.breakpoints, BTreeSet::from([1, 2]));
+
+        execute(&mut debugger, &["continue"]);
+        assert_eq!(debugger.machine.get_program_counter(), 1);
+        execute(&mut debugger, &["continue"]);
+        assert_eq!(debugger.machine.get_program_counter(), 2);
+
+        execute(&mut debugger, &["delete 3", "d loop", "delete loop"]);
+        assert!(debugger.breakpoints.is_empty());
+        execute(&mut debugger, &["continue"]);
+        assert!(debugger.machine.is_stopped());
+    }
+
+    #[test]
+    fn watches_pause_when_the_register_changes() {
+        let mut debugger = debugger(COUNTDOWN, &[(1, 3)]);
+
+        execute(&mut debugger, &["watch r1", "w 1"]);
+        assert_eq!(debugger.watches, BTreeMap::from([(1, 3)]));
+
+        execute(&mut debugger, &["continue"]);
+        assert_eq!(debugger.machine.get_program_counter(), 3);
+        assert_eq!(debugger.watches, BTreeMap::from([(1, 2)]));
+
+        execute(&mut debugger, &["set r1 7", "continue"]);
+        assert_eq!(debugger.watches, BTreeMap::from([(1, 7)]));
+        assert_eq!(debugger.machine.get_program_counter(), 1);
+
+        execute(&mut debugger, &["unwatch r1", "continue"]);
+        assert!(debugger.machine.is_stopped());
+    }
+
+    #[test]
+    fn quit_ends_the_session() {
+        let mut debugger = debugger(COUNTDOWN, &[]);
+
+        assert!(debugger.execute_command("unknown"));
+        assert!(!debugger.execute_command("quit"));
+        assert!(!debugger.execute_command("q"));
+    }
+}
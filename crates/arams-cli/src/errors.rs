@@ -16,9 +16,22 @@ pub(crate) enum Error {
     FailedToParseArgs(clap::Error),
     IOFailedToReadFile(std::io::Error),
     IOFailedToReadFromStdIn(std::io::Error),
+    IOFailedToWriteFile(std::io::Error),
+    IOFailedToDrawTerminal(std::io::Error),
+    InvalidSnapshot(serde_json::Error),
+    InvalidTestCases(String),
+    InvalidExercise(String),
     ARAMSFailedToCompile(Vec<arams_core::CompileError>),
+    ARAMSFailedToLoadBytecode(arams_core::BytecodeError),
+    ARAMSFailedToBuild(arams_core::BytecodeError),
     ARAMSFailedToExecute(arams_core::RuntimeError),
     NoInput,
+    NotFormatted,
+    NoTestCases,
+    NoSubmissions,
+    InputSpaceTooLarge { limit: u64 },
+    ProgramsDiffer { differing: u64, compared: u64 },
+    TestsFailed { failed: usize, total: usize },
 }
 
 impl Error {
@@ -27,9 +40,22 @@ impl Error {
             Error::FailedToParseArgs(_) => StatusCode::SystemError,
             Error::IOFailedToReadFile(_) => StatusCode::SystemError,
             Error::IOFailedToReadFromStdIn(_) => StatusCode::SystemError,
+            Error::IOFailedToWriteFile(_) => StatusCode::SystemError,
+            Error::IOFailedToDrawTerminal(_) => StatusCode::SystemError,
+            Error::InvalidSnapshot(_) => StatusCode::UserError,
+            Error::InvalidTestCases(_) => StatusCode::UserError,
+            Error::InvalidExercise(_) => StatusCode::UserError,
             Error::ARAMSFailedToCompile(_) => StatusCode::ARAMSError,
+            Error::ARAMSFailedToLoadBytecode(_) => StatusCode::ARAMSError,
+            Error::ARAMSFailedToBuild(_) => StatusCode::ARAMSError,
             Error::ARAMSFailedToExecute(_) => StatusCode::ARAMSError,
             Error::NoInput => StatusCode::UserError,
+            Error::NotFormatted => StatusCode::UserError,
+            Error::NoTestCases => StatusCode::UserError,
+            Error::NoSubmissions => StatusCode::UserError,
+            Error::InputSpaceTooLarge { .. } => StatusCode::UserError,
+            Error::ProgramsDiffer { .. } => StatusCode::UserError,
+            Error::TestsFailed { .. } => StatusCode::UserError,
         }
     }
 
@@ -58,12 +84,65 @@ impl std::fmt::Display for Error {
                 e
             ),
             Error::IOFailedToReadFromStdIn(e) => write!(f, "Failed to read from stdin\n{}", e),
+            Error::IOFailedToWriteFile(e) => write!(
+                f,
+                "Failed to write file\nTip: verify the directory exists and permissions are correct.\n{}",
+                e
+            ),
+            Error::IOFailedToDrawTerminal(e) => write!(
+                f,
+                "Failed to draw terminal user interface\nTip: make sure the command runs in an interactive terminal.\n{}",
+                e
+            ),
+            Error::InvalidSnapshot(e) => write!(
+                f,
+                "Failed to read snapshot\nTip: snapshots are created with --pause-after.\n{}",
+                e
+            ),
             Error::NoInput => {
                 write!(
                     f,
                     "No input provided. Please provide a file path, a raw string argument, or pipe data into stdin."
                 )
             }
+            Error::InvalidTestCases(e) => write!(
+                f,
+                "Failed to read test cases\nTip: test cases are declared as [[test]] tables.\n{}",
+                e
+            ),
+            Error::NoTestCases => write!(
+                f,
+                "No test cases found\nTip: declare them with `// @test name: r1 = 5 -> acc = 120` or in a TOML file next to the program."
+            ),
+            Error::InvalidExercise(e) => write!(
+                f,
+                "Failed to read exercise\nTip: an exercise declares its test cases as [[test]] tables.\n{}",
+                e
+            ),
+            Error::NoSubmissions => write!(
+                f,
+                "No submissions found\nTip: pass the submitted files or a directory containing them."
+            ),
+            Error::InputSpaceTooLarge { limit } => write!(
+                f,
+                "The input space has more than {} inputs\nTip: sample it with --samples <COUNT> instead.",
+                limit
+            ),
+            Error::ProgramsDiffer {
+                differing,
+                compared,
+            } => write!(
+                f,
+                "The programs differ on {} of {} inputs",
+                differing, compared
+            ),
+            Error::TestsFailed { failed, total } => {
+                write!(f, "{} of {} test cases failed", failed, total)
+            }
+            Error::NotFormatted => write!(
+                f,
+                "Program is not formatted\nTip: run `arams fmt` to format it."
+            ),
             Error::ARAMSFailedToCompile(errors) => {
                 writeln!(f, "Failed to compile program:")?;
                 for error in errors {
@@ -71,6 +150,10 @@ impl std::fmt::Display for Error {
                 }
                 Ok(())
             }
+            Error::ARAMSFailedToLoadBytecode(e) => {
+                write!(f, "Failed to load compiled program:\n{}", e)
+            }
+            Error::ARAMSFailedToBuild(e) => write!(f, "Failed to build program:\n{}", e),
             Error::ARAMSFailedToExecute(e) => write!(f, "Error while executing program:\n{}", e),
         }
     }
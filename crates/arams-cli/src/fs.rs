@@ -1,7 +1,12 @@
+use arams_core::{Program, Snapshot, TestCase, bytecode};
 use std::fs::File;
-use std::io::{BufRead, BufReader, IsTerminal};
+use std::io::{BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
 
-use crate::errors::Error;
+use crate::{
+    errors::Error,
+    grading::{self, Exercise},
+    test_cases,
+};
 
 pub(crate) fn read_file(file_path: &std::path::Path) -> Result<Vec<String>, Error> {
     let file = File::open(file_path).map_err(Error::IOFailedToReadFile)?;
@@ -15,6 +20,33 @@ pub(crate) fn read_file(file_path: &std::path::Path) -> Result<Vec<String>, Erro
     Ok(lines)
 }
 
+pub(crate) fn is_bytecode_file(file_path: &std::path::Path) -> Result<bool, Error> {
+    let file = File::open(file_path).map_err(Error::IOFailedToReadFile)?;
+
+    let mut magic = Vec::with_capacity(bytecode::MAGIC.len());
+    file.take(bytecode::MAGIC.len() as u64)
+        .read_to_end(&mut magic)
+        .map_err(Error::IOFailedToReadFile)?;
+
+    Ok(bytecode::is_bytecode(&magic))
+}
+
+pub(crate) fn read_bytecode_file(file_path: &std::path::Path) -> Result<Program, Error> {
+    let bytes = std::fs::read(file_path).map_err(Error::IOFailedToReadFile)?;
+
+    bytecode::decode(&bytes).map_err(Error::ARAMSFailedToLoadBytecode)
+}
+
+pub(crate) fn write_bytecode_file(file_path: &std::path::Path, bytes: &[u8]) -> Result<(), Error> {
+    let mut file = File::create(file_path).map_err(Error::IOFailedToWriteFile)?;
+
+    file.write_all(bytes).map_err(Error::IOFailedToWriteFile)
+}
+
+pub(crate) fn write_source_file(file_path: &std::path::Path, source: &str) -> Result<(), Error> {
+    std::fs::write(file_path, source).map_err(Error::IOFailedToWriteFile)
+}
+
 pub(crate) fn read_stdin() -> Result<Vec<String>, Error> {
     let stdin = std::io::stdin();
 
@@ -30,3 +62,36 @@ pub(crate) fn read_stdin() -> Result<Vec<String>, Error> {
 
     Ok(lines)
 }
+
+pub(crate) fn read_snapshot(file_path: &std::path::Path) -> Result<Snapshot, Error> {
+    let file = File::open(file_path).map_err(Error::IOFailedToReadFile)?;
+    let reader = BufReader::new(file);
+
+    serde_json::from_reader(reader).map_err(Error::InvalidSnapshot)
+}
+
+pub(crate) fn write_snapshot(
+    file_path: &std::path::Path,
+    snapshot: &Snapshot,
+) -> Result<(), Error> {
+    let file = File::create(file_path).map_err(Error::IOFailedToWriteFile)?;
+    let writer = BufWriter::new(file);
+
+    serde_json::to_writer_pretty(writer, snapshot).map_err(|e| Error::IOFailedToWriteFile(e.into()))
+}
+
+pub(crate) fn read_test_cases(file_path: &std::path::Path) -> Result<Vec<TestCase>, Error> {
+    let contents = std::fs::read_to_string(file_path).map_err(Error::IOFailedToReadFile)?;
+
+    test_cases::parse_toml(&contents).map_err(Error::InvalidTestCases)
+}
+
+pub(crate) fn read_exercise(file_path: &std::path::Path) -> Result<Exercise, Error> {
+    let contents = std::fs::read_to_string(file_path).map_err(Error::IOFailedToReadFile)?;
+
+    grading::parse_exercise(&contents).map_err(Error::InvalidExercise)
+}
+
+pub(crate) fn write_report(file_path: &std::path::Path, report: &str) -> Result<(), Error> {
+    std::fs::write(file_path, report).map_err(Error::IOFailedToWriteFile)
+}
@@ -0,0 +1,597 @@
+use arams_core::{
+    Address, Instruction, Machine, Program, TestCase, TestOutcome, compile, run_test, step,
+};
+use serde_json::{Value, json};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    errors::Error,
+    fs,
+    output::{OutputFormat, csv_field},
+    registers::parse_key,
+    test_cases::test_cases_from_toml_table,
+};
+
+const DEFAULT_MAX_STEPS: u64 = 1_000_000;
+
+// files next to the submissions that are not programs, e.g. test cases or earlier reports
+const IGNORED_EXTENSIONS: [&str; 4] = ["toml", "json", "csv", "md"];
+
+// an exercise is a TOML file with the test cases every submission has to pass:
+//
+// max_steps = 10000
+// max_instructions = 20
+// required_registers = ["r2"]
+//
+// [[test]]
+// inputs = { r1 = 5 }
+// accumulator = 120
+pub(crate) struct Exercise {
+    test_cases: Vec<TestCase>,
+    max_steps: u64,
+    max_instructions: Option<usize>,
+    required_registers: Vec<usize>,
+}
+
+pub(crate) fn parse_exercise(contents: &str) -> Result<Exercise, String> {
+    let table: toml::Table = toml::from_str(contents).map_err(|e| e.to_string())?;
+
+    for key in table.keys() {
+        if ![
+            "max_steps",
+            "max_instructions",
+            "required_registers",
+            "test",
+        ]
+        .contains(&key.as_str())
+        {
+            return Err(format!("unknown key '{}'", key));
+        }
+    }
+
+    let test_cases = test_cases_from_toml_table(&table)?;
+    if test_cases.is_empty() {
+        return Err("the exercise has no test cases".to_string());
+    }
+
+    let max_steps = match table.get("max_steps") {
+        Some(max_steps) => max_steps
+            .as_integer()
+            .and_then(|max_steps| u64::try_from(max_steps).ok())
+            .ok_or("'max_steps' must be a non-negative integer")?,
+        None => DEFAULT_MAX_STEPS,
+    };
+    let max_instructions = table
+        .get("max_instructions")
+        .map(|max_instructions| {
+            max_instructions
+                .as_integer()
+                .and_then(|max_instructions| usize::try_from(max_instructions).ok())
+                .ok_or("'max_instructions' must be a non-negative integer")
+        })
+        .transpose()?;
+
+    // registers are given as `2` or `"r2"`
+    let required_registers = match table.get("required_registers") {
+        Some(registers) => registers
+            .as_array()
+            .ok_or("'required_registers' must be an array")?
+            .iter()
+            .map(|register| match register {
+                toml::Value::Integer(register) => usize::try_from(*register)
+                    .map_err(|_| format!("Invalid register '{}'", register)),
+                toml::Value::String(register) => parse_key(register),
+                register => Err(format!("Invalid register '{}'", register)),
+            })
+            .collect::<Result<_, _>>()?,
+        None => Vec::new(),
+    };
+
+    Ok(Exercise {
+        test_cases,
+        max_steps,
+        max_instructions,
+        required_registers,
+    })
+}
+
+pub(crate) struct FailedCase {
+    name: String,
+    reason: String,
+}
+
+pub(crate) struct Grade {
+    submission: String,
+    compile_errors: Vec<String>,
+    instructions: Option<usize>,
+    passed: usize,
+    failed_cases: Vec<FailedCase>,
+    contract_violations: Vec<String>,
+    // the uniform cost of the submission: all executed steps of the test cases that stopped
+    cost: u64,
+}
+
+impl Grade {
+    fn status(&self) -> &'static str {
+        if !self.compile_errors.is_empty() {
+            "compile_error"
+        } else if self.failed_cases.is_empty() && self.contract_violations.is_empty() {
+            "passed"
+        } else {
+            "failed"
+        }
+    }
+}
+
+// directories are expanded into the files they contain, sorted by name
+pub(crate) fn collect_submissions(paths: &[PathBuf]) -> Result<Vec<PathBuf>, Error> {
+    let mut submissions = Vec::new();
+
+    for path in paths {
+        if !path.is_dir() {
+            submissions.push(path.clone());
+            continue;
+        }
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(path).map_err(Error::IOFailedToReadFile)? {
+            let file = entry.map_err(Error::IOFailedToReadFile)?.path();
+            let hidden = file
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with('.'));
+            let ignored = file
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| IGNORED_EXTENSIONS.contains(&extension));
+            if file.is_file() && !hidden && !ignored {
+                files.push(file);
+            }
+        }
+        files.sort();
+        submissions.extend(files);
+    }
+
+    if submissions.is_empty() {
+        return Err(Error::NoSubmissions);
+    }
+
+    Ok(submissions)
+}
+
+pub(crate) fn grade(exercise: &Exercise, submission: &Path) -> Grade {
+    let mut grade = Grade {
+        submission: submission.display().to_string(),
+        compile_errors: Vec::new(),
+        instructions: None,
+        passed: 0,
+        failed_cases: Vec::new(),
+        contract_violations: Vec::new(),
+        cost: 0,
+    };
+
+    let program = match load_submission(submission) {
+        Ok(program) => program,
+        Err(errors) => {
+            grade.compile_errors = errors;
+            return grade;
+        }
+    };
+    grade.instructions = Some(program.instructions().len());
+
+    if let Some(max_instructions) = exercise.max_instructions
+        && program.instructions().len() > max_instructions
+    {
+        grade.contract_violations.push(format!(
+            "uses {} instructions, at most {} are allowed",
+            program.instructions().len(),
+            max_instructions
+        ));
+    }
+
+    // the names of the test cases in which a required register is not stored to
+    let mut missing_stores = vec![Vec::new(); exercise.required_registers.len()];
+
+    for test_case in &exercise.test_cases {
+        if !exercise.required_registers.is_empty() {
+            let stored = stored_registers(&program, test_case, exercise.max_steps);
+            for (register, missing) in exercise.required_registers.iter().zip(&mut missing_stores) {
+                if !stored.contains(register) {
+                    missing.push(test_case.name());
+                }
+            }
+        }
+
+        let reason = match run_test(&program, test_case, Some(exercise.max_steps)) {
+            TestOutcome::Passed { steps } => {
+                grade.passed += 1;
+                grade.cost += steps;
+                continue;
+            }
+            TestOutcome::Failed { steps, mismatches } => {
+                grade.cost += steps;
+                mismatches
+                    .iter()
+                    .map(|mismatch| mismatch.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+            TestOutcome::StepLimitExceeded { max_steps } => {
+                format!("did not stop within {} steps", max_steps)
+            }
+            TestOutcome::RuntimeError(err) => err.to_string(),
+        };
+        grade.failed_cases.push(FailedCase {
+            name: test_case.name().to_string(),
+            reason,
+        });
+    }
+
+    for (register, missing) in exercise.required_registers.iter().zip(missing_stores) {
+        if missing.len() == exercise.test_cases.len() {
+            grade
+                .contract_violations
+                .push(format!("never stores to r{}", register));
+        } else if !missing.is_empty() {
+            grade.contract_violations.push(format!(
+                "does not store to r{} in {}",
+                register,
+                missing.join(", ")
+            ));
+        }
+    }
+
+    grade
+}
+
+// the registers a test case run stores to, indirect addresses are resolved with the register values
+// at the time of the store and instructions that are never executed do not count
+fn stored_registers(program: &Program, test_case: &TestCase, max_steps: u64) -> HashSet<usize> {
+    let max_steps = test_case.max_steps().unwrap_or(max_steps);
+    let mut machine =
+        Machine::new_with_preseeded_registers(program.initial_registers(test_case.inputs()));
+    let mut stored = HashSet::new();
+
+    while !machine.is_stopped() && machine.get_steps() < max_steps {
+        let register = match program.fetch(machine.get_program_counter()) {
+            Some(Instruction::Store(Address::Direct(register))) => Some(*register),
+            Some(Instruction::Store(Address::Indirect(pointer))) => {
+                Some(*machine.get_register_value(*pointer) as usize)
+            }
+            _ => None,
+        };
+        if step(program, &mut machine).is_err() {
+            break;
+        }
+        stored.extend(register);
+    }
+
+    stored
+}
+
+// files that cannot be read or decoded are graded like compile errors, one broken submission
+// must not abort the grading of the others
+fn load_submission(submission: &Path) -> Result<Program, Vec<String>> {
+    let program = if fs::is_bytecode_file(submission).map_err(load_error)? {
+        fs::read_bytecode_file(submission).map_err(load_error)?
+    } else {
+        compile(fs::read_file(submission).map_err(load_error)?).map_err(|errors| {
+            errors
+                .iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>()
+        })?
+    };
+
+    // submissions must not preseed registers or bring their own test cases
+    Ok(program.without_directives())
+}
+
+fn load_error(err: Error) -> Vec<String> {
+    match err {
+        Error::IOFailedToReadFile(err) => vec![format!("Failed to read file: {}", err)],
+        Error::ARAMSFailedToLoadBytecode(err) => vec![err.to_string()],
+        err => vec![err.to_string()],
+    }
+}
+
+pub(crate) fn report(format: OutputFormat, grades: &[Grade]) -> String {
+    match format {
+        OutputFormat::Json => report_json(grades),
+        OutputFormat::Csv => report_csv(grades),
+        OutputFormat::Table | OutputFormat::Plain => report_table(grades),
+    }
+}
+
+fn report_json(grades: &[Grade]) -> String {
+    let grades: Vec<Value> = grades
+        .iter()
+        .map(|grade| {
+            let failed_cases: Vec<Value> = grade
+                .failed_cases
+                .iter()
+                .map(|case| json!({ "name": case.name, "reason": case.reason }))
+                .collect();
+            json!({
+                "submission": grade.submission,
+                "status": grade.status(),
+                "passed": grade.passed,
+                "failed": grade.failed_cases.len(),
+                "instructions": grade.instructions,
+                "cost": grade.cost,
+                "failed_cases": failed_cases,
+                "contract_violations": grade.contract_violations,
+                "compile_errors": grade.compile_errors,
+            })
+        })
+        .collect();
+
+    format!(
+        "{}\n",
+        serde_json::to_string_pretty(&grades).unwrap_or_default()
+    )
+}
+
+fn report_csv(grades: &[Grade]) -> String {
+    let mut report = String::from(
+        "submission,status,passed,failed,instructions,cost,failed_cases,contract_violations,compile_errors\n",
+    );
+
+    for grade in grades {
+        let failed_cases: Vec<String> = grade
+            .failed_cases
+            .iter()
+            .map(|case| format!("{}: {}", case.name, case.reason))
+            .collect();
+        report.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            csv_field(&grade.submission),
+            grade.status(),
+            grade.passed,
+            grade.failed_cases.len(),
+            grade
+                .instructions
+                .map(|instructions| instructions.to_string())
+                .unwrap_or_default(),
+            grade.cost,
+            csv_field(&failed_cases.join("; ")),
+            csv_field(&grade.contract_violations.join("; ")),
+            csv_field(&grade.compile_errors.join("; ")),
+        ));
+    }
+
+    report
+}
+
+fn report_table(grades: &[Grade]) -> String {
+    let width = grades
+        .iter()
+        .map(|grade| grade.submission.len())
+        .max()
+        .unwrap_or(0)
+        .max("Submission".len());
+
+    let mut report = format!(
+        "{:<width$}  {:<13}  {:>6}  {:>12}  {:>10}\n",
+        "Submission", "Status", "Passed", "Instructions", "Cost"
+    );
+    for grade in grades {
+        report.push_str(&format!(
+            "{:<width$}  {:<13}  {:>6}  {:>12}  {:>10}\n",
+            grade.submission,
+            grade.status(),
+            match grade.instructions {
+                Some(_) => format!(
+                    "{}/{}",
+                    grade.passed,
+                    grade.passed + grade.failed_cases.len()
+                ),
+                None => "-".to_string(),
+            },
+            grade
+                .instructions
+                .map(|instructions| instructions.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            grade.cost
+        ));
+        for error in &grade.compile_errors {
+            report.push_str(&format!("    {}\n", error));
+        }
+        for violation in &grade.contract_violations {
+            report.push_str(&format!("    {}\n", violation));
+        }
+        for case in &grade.failed_cases {
+            report.push_str(&format!("    {}: {}\n", case.name, case.reason));
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXERCISE: &str = "[[test]]\ninputs = { r1 = 5 }\naccumulator = 5\n";
+
+    // a fresh directory per test, the tests run in parallel
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("arams-grading-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn grade_with(submission: &str, compile_errors: Vec<String>) -> Grade {
+        Grade {
+            submission: submission.to_string(),
+            compile_errors,
+            instructions: None,
+            passed: 0,
+            failed_cases: Vec::new(),
+            contract_violations: Vec::new(),
+            cost: 0,
+        }
+    }
+
+    #[test]
+    fn exercise_defaults() {
+        let exercise = parse_exercise(EXERCISE).unwrap();
+
+        assert_eq!(exercise.test_cases.len(), 1);
+        assert_eq!(exercise.max_steps, DEFAULT_MAX_STEPS);
+        assert_eq!(exercise.max_instructions, None);
+        assert!(exercise.required_registers.is_empty());
+    }
+
+    #[test]
+    fn exercise_rejects_unknown_keys() {
+        let err = parse_exercise(&format!("max_step = 10\n{}", EXERCISE)).err();
+
+        assert_eq!(err.as_deref(), Some("unknown key 'max_step'"));
+    }
+
+    #[test]
+    fn exercise_without_test_cases_is_rejected() {
+        let err = parse_exercise("max_steps = 10\n").err();
+
+        assert_eq!(err.as_deref(), Some("the exercise has no test cases"));
+    }
+
+    #[test]
+    fn required_registers_are_numbers_or_names() {
+        let exercise = parse_exercise(&format!(
+            "max_steps = 10\nmax_instructions = 4\nrequired_registers = [2, \"r3\", \"R4\"]\n{}",
+            EXERCISE
+        ))
+        .unwrap();
+
+        assert_eq!(exercise.max_steps, 10);
+        assert_eq!(exercise.max_instructions, Some(4));
+        assert_eq!(exercise.required_registers, vec![2, 3, 4]);
+
+        for registers in ["[-1]", "[true]", "\"r2\""] {
+            let contents = format!("required_registers = {}\n{}", registers, EXERCISE);
+            assert!(parse_exercise(&contents).is_err(), "{}", registers);
+        }
+    }
+
+    #[test]
+    fn directories_are_expanded_without_hidden_and_ignored_files() {
+        let dir = temp_dir("collect");
+        for file in [
+            "b.ram",
+            "a.ram",
+            ".hidden.ram",
+            "exercise.toml",
+            "report.csv",
+            "notes.md",
+        ] {
+            std::fs::write(dir.join(file), "end").unwrap();
+        }
+        std::fs::create_dir(dir.join("nested")).unwrap();
+        let single = dir.join("exercise.toml");
+
+        let submissions = collect_submissions(&[dir.clone(), single.clone()]).unwrap();
+
+        assert_eq!(
+            submissions,
+            vec![dir.join("a.ram"), dir.join("b.ram"), single]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn empty_directories_have_no_submissions() {
+        let dir = temp_dir("empty");
+
+        assert!(matches!(
+            collect_submissions(std::slice::from_ref(&dir)),
+            Err(Error::NoSubmissions)
+        ));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn undecodable_submissions_are_compile_errors() {
+        let dir = temp_dir("undecodable");
+        let exercise = parse_exercise(EXERCISE).unwrap();
+        std::fs::write(dir.join("latin1.ram"), b"load 1 // \xe9\nend").unwrap();
+        std::fs::write(dir.join("broken.ramc"), b"RAMC\x01\x00\xff\xff\xff\xff").unwrap();
+        std::fs::write(dir.join("valid.ram"), "load 1\nend").unwrap();
+
+        let unreadable = grade(&exercise, &dir.join("latin1.ram"));
+        let broken = grade(&exercise, &dir.join("broken.ramc"));
+        let missing = grade(&exercise, &dir.join("missing.ram"));
+        let valid = grade(&exercise, &dir.join("valid.ram"));
+
+        for grade in [&unreadable, &broken, &missing] {
+            assert_eq!(grade.status(), "compile_error", "{}", grade.submission);
+            assert_eq!(grade.compile_errors.len(), 1);
+        }
+        assert!(unreadable.compile_errors[0].starts_with("Failed to read file"));
+        assert_eq!(valid.status(), "passed");
+        assert_eq!(valid.instructions, Some(2));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn required_registers_have_to_be_stored_while_running() {
+        let dir = temp_dir("required");
+        let exercise = parse_exercise(
+            "required_registers = [\"r2\"]\n\
+             [[test]]\ninputs = { r1 = 5 }\naccumulator = 5\n\
+             [[test]]\nname = \"zero\"\ninputs = { r1 = 0 }\naccumulator = 0\n",
+        )
+        .unwrap();
+        let submissions = [
+            ("direct.ram", "load 1\nstore 2\nend"),
+            ("indirect.ram", "load #2\nstore 3\nload 1\nstore *3\nend"),
+            ("dead.ram", "load 1\ngoto done\nstore 2\ndone: end"),
+            ("partial.ram", "load 1\njzero done\nstore 2\ndone: end"),
+        ];
+        for (name, source) in submissions {
+            std::fs::write(dir.join(name), source).unwrap();
+        }
+
+        let violations: Vec<Vec<String>> = submissions
+            .iter()
+            .map(|(name, _)| grade(&exercise, &dir.join(name)).contract_violations)
+            .collect();
+
+        assert_eq!(
+            violations,
+            [
+                vec![],
+                vec![],
+                vec!["never stores to r2".to_string()],
+                vec!["does not store to r2 in zero".to_string()],
+            ]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn csv_report_quotes_fields() {
+        let mut failed = grade_with("sub, \"one\".ram", Vec::new());
+        failed.instructions = Some(3);
+        failed.passed = 1;
+        failed.cost = 7;
+        failed.failed_cases.push(FailedCase {
+            name: "case 2".to_string(),
+            reason: "expected 1, got 2".to_string(),
+        });
+        let broken = grade_with("two.ram", vec!["a\nb".to_string(), "c".to_string()]);
+
+        assert_eq!(
+            report_csv(&[failed, broken]),
+            "submission,status,passed,failed,instructions,cost,failed_cases,contract_violations,compile_errors\n\
+             \"sub, \"\"one\"\".ram\",failed,1,1,3,7,\"case 2: expected 1, got 2\",,\n\
+             two.ram,compile_error,0,0,,0,,,\"a\nb; c\"\n"
+        );
+    }
+}
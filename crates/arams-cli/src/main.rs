@@ -1,46 +1,329 @@
-use arams_core::{IntoSourceCode, compile, execute};
+use arams_core::{
+    IntoSourceCode, Machine, Program, Snapshot, bytecode, compile, disassemble, lsp, optimize,
+    run_test, step,
+};
+use std::path::PathBuf;
 
 use crate::{
-    args::{Input, parse_args_or_exit},
+    args::{Args, Command, Input, parse_args_or_exit},
     errors::{Error, StatusCode},
 };
 
 mod args;
+mod compare;
 mod constants;
+mod debugger;
 mod errors;
 mod fs;
+mod grading;
+mod output;
+mod registers;
+mod test_cases;
+mod tui;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    match run() {
-        Ok(_) => std::process::exit(StatusCode::Success.into()),
+    let args = match parse_args_or_exit() {
+        Ok(args) => args,
         Err(e) => {
+            let e = Error::FailedToParseArgs(e);
             eprintln!("{}", e.standalone_message());
             std::process::exit(e.status_code().into());
         }
+    };
+
+    match run(&args) {
+        Ok(_) => std::process::exit(StatusCode::Success.into()),
+        Err(e) => {
+            output::print_error(args.output_format(), &e);
+            std::process::exit(e.status_code().into());
+        }
     }
 }
 
-fn run() -> Result<(), Error> {
-    let args = parse_args_or_exit().map_err(Error::FailedToParseArgs)?;
+fn run(args: &Args) -> Result<(), Error> {
+    match args.command() {
+        Command::Run => {}
+        Command::Check => {
+            let program = load_program(args.input())?;
+            output::print_check(args.output_format(), &program);
+            return Ok(());
+        }
+        Command::Trace => {
+            let program = load_program(args.input())?;
+            let mut machine =
+                Machine::new_with_preseeded_registers(program.initial_registers(args.registers()));
+
+            let format = args.output_format();
+            let streaming = matches!(
+                format,
+                output::OutputFormat::Plain | output::OutputFormat::Table
+            );
+            let mut rows = Vec::new();
+            while !machine.is_stopped() {
+                let program_counter = machine.get_program_counter();
+                let instruction = program.fetch(program_counter).map(ToString::to_string);
+                step(&program, &mut machine).map_err(Error::ARAMSFailedToExecute)?;
+
+                // running past the last instruction stops the machine without executing anything
+                let Some(instruction) = instruction else {
+                    if streaming {
+                        println!("{:>6}  end of program", "");
+                    }
+                    continue;
+                };
+                let row = output::TraceRow::new(
+                    machine.get_steps(),
+                    program_counter,
+                    program.source_line(program_counter),
+                    instruction,
+                    machine.get_accumulator(),
+                );
+                if streaming {
+                    output::print_trace_row(&row);
+                } else {
+                    rows.push(row);
+                }
+            }
+
+            output::print_trace(format, &rows, &machine);
+            return Ok(());
+        }
+        Command::Fmt { output, check } => {
+            let source = read_source(args.input())?;
+            // formatting must never change the meaning of a program
+            compile(source.clone()).map_err(Error::ARAMSFailedToCompile)?;
+            let formatted = lsp::format(source.clone());
+
+            if *check {
+                if !formatted.lines().eq(source.iter().map(String::as_str)) {
+                    return Err(Error::NotFormatted);
+                }
+                return Ok(());
+            }
 
-    let contents = match args.input() {
+            return match output {
+                Some(output) => fs::write_source_file(output, &formatted),
+                None => {
+                    print!("{}", formatted);
+                    Ok(())
+                }
+            };
+        }
+        Command::Analyze => {
+            let source = read_source(args.input())?;
+            for (line_number, tokens) in lsp::analyze(source).iter().enumerate() {
+                for token in tokens {
+                    if token.kind() == &lsp::LSPTokenKind::NewLine {
+                        continue;
+                    }
+                    println!(
+                        "{}: {:<26} {}",
+                        line_number + 1,
+                        token.kind().to_string(),
+                        token.lexeme()
+                    );
+                    for error in token.errors() {
+                        println!("    error: {}", error);
+                    }
+                }
+            }
+            return Ok(());
+        }
+        Command::Test { cases, max_steps } => {
+            return run_tests(args.input(), cases.as_ref(), *max_steps);
+        }
+        Command::Compare {
+            reference,
+            candidate,
+            options,
+        } => {
+            let comparison = compare::compare(
+                &load_program(reference)?,
+                &load_program(candidate)?,
+                options,
+            )?;
+            compare::print_comparison(args.output_format(), &comparison);
+
+            if comparison.differing() > 0 {
+                return Err(Error::ProgramsDiffer {
+                    differing: comparison.differing(),
+                    compared: comparison.compared(),
+                });
+            }
+            return Ok(());
+        }
+        Command::Grade {
+            spec,
+            submissions,
+            report,
+        } => {
+            let exercise = fs::read_exercise(spec)?;
+            let grades = grading::collect_submissions(submissions)?
+                .iter()
+                .map(|submission| grading::grade(&exercise, submission))
+                .collect::<Vec<_>>();
+
+            if let Some(report) = report {
+                let format = match report.extension().and_then(|ext| ext.to_str()) {
+                    Some("json") => output::OutputFormat::Json,
+                    _ => output::OutputFormat::Csv,
+                };
+                fs::write_report(report, &grading::report(format, &grades))?;
+            }
+            print!("{}", grading::report(args.output_format(), &grades));
+            return Ok(());
+        }
+        Command::Build {
+            output,
+            strip_debug_info,
+        } => {
+            let program = load_program(args.input())?;
+            let bytes =
+                bytecode::encode(&program, !strip_debug_info).map_err(Error::ARAMSFailedToBuild)?;
+            return fs::write_bytecode_file(output, &bytes);
+        }
+        Command::Optimize { output } => {
+            let program = load_program(args.input())?;
+            let (optimized, report) = optimize(&program);
+
+            println!("Optimization Report:");
+            print!("{}", report);
+
+            return match output {
+                Some(output) => fs::write_source_file(output, &disassemble(&optimized)),
+                None => {
+                    println!("Optimized Program:");
+                    print!("{}", disassemble(&optimized));
+                    Ok(())
+                }
+            };
+        }
+        Command::Debug => {
+            let (program, source) = load_program_with_source(args.input())?;
+            let registers = program.initial_registers(args.registers());
+            return debugger::debug(program, source, registers);
+        }
+        Command::Tui => {
+            let (program, source) = load_program_with_source(args.input())?;
+            let registers = program.initial_registers(args.registers());
+            return tui::tui(program, source, registers);
+        }
+    }
+
+    let mut snapshot = match args.resume() {
+        Some(snapshot_path) => fs::read_snapshot(snapshot_path)?,
+        None => {
+            let program = load_program(args.input())?;
+            let registers = program.initial_registers(args.registers());
+
+            Snapshot::new(program, Machine::new_with_preseeded_registers(registers))
+        }
+    };
+
+    if let Some(steps) = args.pause_after() {
+        for _ in 0..steps {
+            if snapshot.machine().is_stopped() {
+                break;
+            }
+            snapshot.step().map_err(Error::ARAMSFailedToExecute)?;
+        }
+
+        if !snapshot.machine().is_stopped() {
+            match args.snapshot() {
+                Some(snapshot_path) => fs::write_snapshot(snapshot_path, &snapshot)?,
+                None => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&snapshot).map_err(Error::InvalidSnapshot)?
+                ),
+            }
+            return Ok(());
+        }
+    }
+
+    let machine = snapshot.resume().map_err(Error::ARAMSFailedToExecute)?;
+    output::print_machine(args.output_format(), &machine);
+
+    Ok(())
+}
+
+fn load_program(input: &Input) -> Result<Program, Error> {
+    let contents = match input {
+        Input::File(file_path) if fs::is_bytecode_file(file_path)? => {
+            return fs::read_bytecode_file(file_path);
+        }
         Input::File(file_path) => fs::read_file(file_path)?,
         Input::Raw(raw_string) => raw_string.into_lines(),
         Input::None => fs::read_stdin()?,
     };
 
-    let registers = args.registers();
+    compile(contents).map_err(Error::ARAMSFailedToCompile)
+}
+
+fn read_source(input: &Input) -> Result<Vec<String>, Error> {
+    match input {
+        // compiled programs are shown using their disassembly
+        Input::File(file_path) if fs::is_bytecode_file(file_path)? => {
+            Ok(disassemble(&fs::read_bytecode_file(file_path)?).into_lines())
+        }
+        Input::File(file_path) => fs::read_file(file_path),
+        Input::Raw(raw_string) => Ok(raw_string.into_lines()),
+        Input::None => fs::read_stdin(),
+    }
+}
+
+// interactive commands need the source code to show the current line and stdin for user input
+fn load_program_with_source(input: &Input) -> Result<(Program, Vec<String>), Error> {
+    if let Input::None = input {
+        return Err(Error::NoInput);
+    }
+    let contents = read_source(input)?;
+
+    let program = compile(contents.clone()).map_err(Error::ARAMSFailedToCompile)?;
+
+    Ok((program, contents))
+}
+
+fn run_tests(input: &Input, cases: Option<&PathBuf>, max_steps: u64) -> Result<(), Error> {
+    let program = load_program(input)?;
+
+    // `factorial.ram` picks up the test cases in `factorial.toml`
+    let cases = cases.cloned().or_else(|| match input {
+        Input::File(file_path) => Some(file_path.with_extension("toml"))
+            .filter(|cases| cases != file_path && cases.is_file()),
+        _ => None,
+    });
+
+    let mut test_cases = program.test_cases().to_vec();
+    if let Some(cases) = cases {
+        test_cases.extend(fs::read_test_cases(&cases)?);
+    }
+    if test_cases.is_empty() {
+        return Err(Error::NoTestCases);
+    }
+
+    println!("running {} test cases", test_cases.len());
+    let mut failed = 0;
+    for test_case in &test_cases {
+        let outcome = run_test(&program, test_case, Some(max_steps));
+        if !outcome.is_passed() {
+            failed += 1;
+        }
+        println!("test {} ... {}", test_case.name(), outcome);
+    }
 
-    let program = compile(contents).map_err(Error::ARAMSFailedToCompile)?;
-    let machine = execute(program, Some(registers.clone())).map_err(Error::ARAMSFailedToExecute)?;
+    println!();
+    println!(
+        "test result: {}. {} passed; {} failed",
+        if failed == 0 { "ok" } else { "FAILED" },
+        test_cases.len() - failed,
+        failed
+    );
 
-    println!("Machine State After Execution:");
-    println!("Accumulator: {}", machine.get_accumulator());
-    println!("Registers:");
-    let mut registers: Vec<_> = machine.get_registers().iter().collect();
-    registers.sort_by_key(|(key, _)| *key);
-    for (key, value) in registers {
-        println!("  {}: {}", key, value);
+    if failed > 0 {
+        return Err(Error::TestsFailed {
+            failed,
+            total: test_cases.len(),
+        });
     }
 
     Ok(())
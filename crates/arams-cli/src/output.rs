@@ -0,0 +1,455 @@
+use arams_core::{CompileError, Machine, Program, RuntimeError};
+use serde_json::{Value, json};
+
+use crate::errors::Error;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum OutputFormat {
+    Json,
+    Csv,
+    Table,
+    #[default]
+    Plain,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "table" => Ok(OutputFormat::Table),
+            "plain" => Ok(OutputFormat::Plain),
+            _ => Err(format!(
+                "Invalid output format '{}', expected one of json, csv, table, plain",
+                s
+            )),
+        }
+    }
+}
+
+// an executed instruction of `arams trace` and the accumulator after it
+pub(crate) struct TraceRow {
+    step: u64,
+    program_counter: usize,
+    line: Option<usize>,
+    instruction: String,
+    accumulator: u64,
+}
+
+impl TraceRow {
+    pub(crate) fn new(
+        step: u64,
+        program_counter: usize,
+        line: Option<usize>,
+        instruction: String,
+        accumulator: u64,
+    ) -> Self {
+        Self {
+            step,
+            program_counter,
+            line,
+            instruction,
+            accumulator,
+        }
+    }
+}
+
+pub(crate) fn print_machine(format: OutputFormat, machine: &Machine) {
+    print!("{}", machine_output(format, machine));
+}
+
+// plain and table rows are printed while the program runs, json and csv once it has stopped
+pub(crate) fn print_trace_row(row: &TraceRow) {
+    print!("{}", trace_row_output(row));
+}
+
+pub(crate) fn print_trace(format: OutputFormat, rows: &[TraceRow], machine: &Machine) {
+    print!("{}", trace_output(format, rows, machine));
+}
+
+pub(crate) fn print_check(format: OutputFormat, program: &Program) {
+    print!("{}", check_output(format, program));
+}
+
+// compile and runtime errors are part of the structured output, all other errors are printed to stderr
+pub(crate) fn print_error(format: OutputFormat, error: &Error) {
+    match (format, error) {
+        (OutputFormat::Plain, _) => eprintln!("{}", error.standalone_message()),
+        (_, Error::ARAMSFailedToCompile(errors)) => {
+            print!("{}", compile_errors_output(format, errors))
+        }
+        (_, Error::ARAMSFailedToExecute(error)) => {
+            print!("{}", runtime_error_output(format, error))
+        }
+        _ => eprintln!("{}", error.standalone_message()),
+    }
+}
+
+fn machine_json(machine: &Machine) -> Value {
+    let registers: serde_json::Map<String, Value> = machine
+        .get_registers()
+        .iter()
+        .map(|(register, value)| (register.to_string(), json!(value)))
+        .collect();
+
+    json!({
+        "status": "ok",
+        "accumulator": machine.get_accumulator(),
+        "registers": registers,
+        "stop_reason": machine
+            .get_stop_reason()
+            .map(|reason| stop_reason_name(&reason))
+            .unwrap_or_default(),
+        "steps": machine.get_steps(),
+    })
+}
+
+fn machine_output(format: OutputFormat, machine: &Machine) -> String {
+    let mut registers: Vec<_> = machine.get_registers().iter().collect();
+    registers.sort_by_key(|(key, _)| *key);
+    let stop_reason = machine
+        .get_stop_reason()
+        .map(|reason| stop_reason_name(&reason))
+        .unwrap_or_default();
+
+    match format {
+        OutputFormat::Json => format!("{}\n", machine_json(machine)),
+        OutputFormat::Csv => {
+            let mut output = String::from("name,value\n");
+            output.push_str(&format!("accumulator,{}\n", machine.get_accumulator()));
+            output.push_str(&format!("stop_reason,{}\n", stop_reason));
+            output.push_str(&format!("steps,{}\n", machine.get_steps()));
+            for (register, value) in registers {
+                output.push_str(&format!("r{},{}\n", register, value));
+            }
+            output
+        }
+        OutputFormat::Table => {
+            let mut output = format!("{:<12} {}\n", "Accumulator", machine.get_accumulator());
+            if let Some(reason) = machine.get_stop_reason() {
+                output.push_str(&format!("{:<12} {}\n", "Stop reason", reason));
+            }
+            output.push_str(&format!("{:<12} {}\n", "Steps", machine.get_steps()));
+            output.push_str(&format!("\n{:<12} Value\n", "Register"));
+            for (register, value) in registers {
+                output.push_str(&format!("{:<12} {}\n", register, value));
+            }
+            output
+        }
+        OutputFormat::Plain => {
+            let mut output = String::from("Machine State After Execution:\n");
+            output.push_str(&format!("Accumulator: {}\n", machine.get_accumulator()));
+            output.push_str("Registers:\n");
+            for (key, value) in registers {
+                output.push_str(&format!("  {}: {}\n", key, value));
+            }
+            output
+        }
+    }
+}
+
+fn trace_row_output(row: &TraceRow) -> String {
+    let location = match row.line {
+        Some(line) => format!("line {}", line),
+        None => format!("#{}", row.program_counter),
+    };
+
+    format!(
+        "{:>6}  {:<10} {:<20} acc = {}\n",
+        row.step, location, row.instruction, row.accumulator
+    )
+}
+
+// the json object of `arams run` with the executed instructions as `trace`, csv has only the rows
+fn trace_output(format: OutputFormat, rows: &[TraceRow], machine: &Machine) -> String {
+    match format {
+        OutputFormat::Json => {
+            let mut output = machine_json(machine);
+            output["trace"] = rows
+                .iter()
+                .map(|row| {
+                    json!({
+                        "step": row.step,
+                        "line": row.line,
+                        "instruction": row.instruction,
+                        "accumulator": row.accumulator,
+                    })
+                })
+                .collect();
+            format!("{}\n", output)
+        }
+        OutputFormat::Csv => {
+            let mut output = String::from("step,line,instruction,accumulator\n");
+            for row in rows {
+                output.push_str(&format!(
+                    "{},{},{},{}\n",
+                    row.step,
+                    row.line.map(|line| line.to_string()).unwrap_or_default(),
+                    csv_field(&row.instruction),
+                    row.accumulator
+                ));
+            }
+            output
+        }
+        OutputFormat::Table | OutputFormat::Plain => machine_output(format, machine),
+    }
+}
+
+fn check_output(format: OutputFormat, program: &Program) -> String {
+    match format {
+        OutputFormat::Json => format!(
+            "{}\n",
+            json!({
+                "status": "ok",
+                "instructions": program.instructions().len(),
+                "errors": [],
+            })
+        ),
+        OutputFormat::Csv => "line,kind,message\n".to_string(),
+        OutputFormat::Table | OutputFormat::Plain => format!(
+            "No errors found ({} instructions)\n",
+            program.instructions().len()
+        ),
+    }
+}
+
+fn compile_errors_output(format: OutputFormat, errors: &[CompileError]) -> String {
+    match format {
+        OutputFormat::Json => {
+            let errors: Vec<Value> = errors
+                .iter()
+                .map(|error| {
+                    json!({
+                        "line": error.line_number(),
+                        "kind": format!("{:?}", error.kind()),
+                        "context": error.context(),
+                        "message": error.to_string(),
+                    })
+                })
+                .collect();
+            format!(
+                "{}\n",
+                json!({
+                    "status": "compile_error",
+                    "errors": errors,
+                })
+            )
+        }
+        OutputFormat::Csv => {
+            let mut output = String::from("line,kind,message\n");
+            for error in errors {
+                output.push_str(&format!(
+                    "{},{:?},{}\n",
+                    error.line_number(),
+                    error.kind(),
+                    csv_field(&error.to_string())
+                ));
+            }
+            output
+        }
+        OutputFormat::Table | OutputFormat::Plain => {
+            let mut output = format!("{:<6} {:<24} Message\n", "Line", "Kind");
+            for error in errors {
+                output.push_str(&format!(
+                    "{:<6} {:<24} {}\n",
+                    error.line_number(),
+                    format!("{:?}", error.kind()),
+                    error
+                ));
+            }
+            output
+        }
+    }
+}
+
+fn runtime_error_output(format: OutputFormat, error: &RuntimeError) -> String {
+    match format {
+        OutputFormat::Json => format!(
+            "{}\n",
+            json!({
+                "status": "runtime_error",
+                "error": {
+                    "line": error.line_number(),
+                    "message": error.to_string(),
+                },
+            })
+        ),
+        OutputFormat::Csv => format!(
+            "line,message\n{},{}\n",
+            error
+                .line_number()
+                .map(|line| line.to_string())
+                .unwrap_or_default(),
+            csv_field(&error.to_string())
+        ),
+        OutputFormat::Table | OutputFormat::Plain => format!(
+            "{:<6} Message\n{:<6} {}\n",
+            "Line",
+            error
+                .line_number()
+                .map(|line| line.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            error
+        ),
+    }
+}
+
+fn stop_reason_name(reason: &arams_core::StopReason) -> &'static str {
+    match reason {
+        arams_core::StopReason::End => "end",
+        arams_core::StopReason::EndOfProgram => "end_of_program",
+        arams_core::StopReason::DivisionByZero => "division_by_zero",
+    }
+}
+
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arams_core::{compile, execute};
+    use std::collections::HashMap;
+
+    fn json_of(output: &str) -> Value {
+        assert_eq!(output.lines().count(), 1, "{}", output);
+        serde_json::from_str(output).unwrap()
+    }
+
+    fn factorial_of_three() -> Machine {
+        let program = compile(
+            "load 1\nstore 2\nloop: load 1\nsub #1\njzero done\nstore 1\nmul 2\nstore 2\ngoto loop\ndone: load 2\nend",
+        )
+        .unwrap();
+        execute(program, Some(HashMap::from([(1, 3)]))).unwrap()
+    }
+
+    #[test]
+    fn machine_json() {
+        let output = machine_output(OutputFormat::Json, &factorial_of_three());
+
+        assert_eq!(
+            json_of(&output),
+            json!({
+                "status": "ok",
+                "accumulator": 6,
+                "registers": { "1": 1, "2": 6 },
+                "stop_reason": "end",
+                "steps": 21,
+            })
+        );
+
+        let machine = execute(compile("load #4\ndiv #0\nend").unwrap(), None).unwrap();
+        let output = json_of(&machine_output(OutputFormat::Json, &machine));
+        assert_eq!(output["stop_reason"], "division_by_zero");
+        assert_eq!(output["registers"], json!({}));
+    }
+
+    #[test]
+    fn machine_csv() {
+        assert_eq!(
+            machine_output(OutputFormat::Csv, &factorial_of_three()),
+            "name,value\naccumulator,6\nstop_reason,end\nsteps,21\nr1,1\nr2,6\n"
+        );
+    }
+
+    #[test]
+    fn trace_rows() {
+        let machine = execute(compile("load #2\nmul #3\nend").unwrap(), None).unwrap();
+        let rows = [
+            TraceRow::new(1, 0, Some(1), "load #2".to_string(), 2),
+            TraceRow::new(2, 1, Some(2), "mul #3".to_string(), 6),
+            TraceRow::new(3, 2, None, "end".to_string(), 6),
+        ];
+
+        let output = json_of(&trace_output(OutputFormat::Json, &rows, &machine));
+        assert_eq!(output["accumulator"], 6);
+        assert_eq!(output["steps"], 3);
+        assert_eq!(
+            output["trace"][1],
+            json!({ "step": 2, "line": 2, "instruction": "mul #3", "accumulator": 6 })
+        );
+        assert_eq!(output["trace"][2]["line"], Value::Null);
+
+        assert_eq!(
+            trace_output(OutputFormat::Csv, &rows, &machine),
+            "step,line,instruction,accumulator\n1,1,load #2,2\n2,2,mul #3,6\n3,,end,6\n"
+        );
+        assert_eq!(
+            trace_row_output(&rows[2]),
+            "     3  #2         end                  acc = 6\n"
+        );
+    }
+
+    #[test]
+    fn check_output_has_no_errors() {
+        let program = compile("load 1\nend").unwrap();
+
+        assert_eq!(
+            json_of(&check_output(OutputFormat::Json, &program)),
+            json!({ "status": "ok", "instructions": 2, "errors": [] })
+        );
+        assert_eq!(
+            check_output(OutputFormat::Csv, &program),
+            "line,kind,message\n"
+        );
+    }
+
+    #[test]
+    fn compile_errors() {
+        let errors = compile("load x\nstore\nend").unwrap_err();
+
+        let output = json_of(&compile_errors_output(OutputFormat::Json, &errors));
+        assert_eq!(output["status"], "compile_error");
+        let errors_json = output["errors"].as_array().unwrap();
+        assert_eq!(errors_json.len(), errors.len());
+        assert_eq!(errors_json[0]["line"], 1);
+        assert_eq!(errors_json[0]["kind"], "InvalidArgument");
+        assert_eq!(errors_json[0]["context"], "x");
+        assert_eq!(errors_json[0]["message"], errors[0].to_string());
+
+        let csv = compile_errors_output(OutputFormat::Csv, &errors);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("line,kind,message"));
+        assert!(lines.next().unwrap().starts_with("1,InvalidArgument,"));
+    }
+
+    #[test]
+    fn runtime_errors() {
+        let error = execute(compile("load #1\ngoto missing\nend").unwrap(), None).unwrap_err();
+
+        assert_eq!(
+            json_of(&runtime_error_output(OutputFormat::Json, &error)),
+            json!({
+                "status": "runtime_error",
+                "error": { "line": 2, "message": error.to_string() },
+            })
+        );
+        assert_eq!(
+            runtime_error_output(OutputFormat::Csv, &error),
+            format!("line,message\n2,{}\n", csv_field(&error.to_string()))
+        );
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn formats_are_parsed() {
+        assert_eq!("json".parse(), Ok(OutputFormat::Json));
+        assert_eq!("table".parse(), Ok(OutputFormat::Table));
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+}
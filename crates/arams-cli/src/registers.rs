@@ -0,0 +1,528 @@
+use std::{collections::HashMap, ops::RangeInclusive};
+
+// ranges larger than this are most likely a typo and would allocate a huge register map
+const MAX_RANGE_LENGTH: usize = 1_000_000;
+
+#[derive(Debug)]
+pub(crate) struct RegisterError {
+    input: String,
+    position: usize,
+    message: String,
+}
+
+impl RegisterError {
+    fn new(input: &str, position: usize, message: impl Into<String>) -> Self {
+        Self {
+            input: input.to_string(),
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for RegisterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let offset = self.input[..self.position.min(self.input.len())]
+            .chars()
+            .count();
+        write!(
+            f,
+            "{}\n  {}\n  {}^",
+            self.message,
+            self.input,
+            " ".repeat(offset)
+        )
+    }
+}
+
+impl std::error::Error for RegisterError {}
+
+// `1=5`, `r1=5`, `10..=20=0`, `10..20=0`, comma separated lists of these or the old `[(1,2),(2,4)]` format
+pub(crate) fn parse_assignments(input: &str) -> Result<Vec<(usize, u64)>, RegisterError> {
+    if input.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut parser = Parser::new(input);
+    parser.skip_whitespace();
+
+    let assignments = if parser.peek() == Some('[') {
+        parser.parse_pairs()?
+    } else {
+        parser.parse_assignment_list()?
+    };
+
+    parser.skip_whitespace();
+    parser.expect_end()?;
+
+    Ok(assignments)
+}
+
+// `10:3,1,4,1,5` fills the registers 10 to 14
+pub(crate) fn parse_array(input: &str) -> Result<Vec<(usize, u64)>, RegisterError> {
+    let mut parser = Parser::new(input);
+    parser.skip_whitespace();
+
+    let base = parser.parse_register()?;
+    parser.skip_whitespace();
+    parser.expect(':')?;
+
+    let mut assignments = Vec::new();
+    loop {
+        parser.skip_whitespace();
+        let position = parser.position;
+        let value = parser.parse_value()?;
+        let register = base
+            .checked_add(assignments.len())
+            .ok_or_else(|| RegisterError::new(input, position, "Register address is too large"))?;
+        assignments.push((register, value));
+
+        parser.skip_whitespace();
+        if !parser.eat(',') {
+            break;
+        }
+    }
+
+    parser.expect_end()?;
+
+    Ok(assignments)
+}
+
+// `r1=0..=10`, `r2=0..5` or `r3=7`, comma separated, the ranges are the values a register can take
+pub(crate) fn parse_value_ranges(
+    input: &str,
+) -> Result<Vec<(usize, RangeInclusive<u64>)>, RegisterError> {
+    let mut parser = Parser::new(input);
+    let mut ranges = Vec::new();
+
+    loop {
+        parser.skip_whitespace();
+        let register = parser.parse_register()?;
+        parser.skip_whitespace();
+        parser.expect('=')?;
+        parser.skip_whitespace();
+
+        let start = parser.position;
+        let first = parser.parse_value()?;
+        let last = if parser.eat_str("..=") {
+            parser.parse_value()?
+        } else if parser.eat_str("..") {
+            let position = parser.position;
+            let end = parser.parse_value()?;
+            end.checked_sub(1)
+                .ok_or_else(|| RegisterError::new(input, position, "Range is empty"))?
+        } else {
+            first
+        };
+        if last < first {
+            return Err(RegisterError::new(input, start, "Range is empty"));
+        }
+        ranges.push((register, first..=last));
+
+        parser.skip_whitespace();
+        if !parser.eat(',') {
+            break;
+        }
+    }
+
+    parser.expect_end()?;
+
+    Ok(ranges)
+}
+
+// `{"1": 5, "r2": 3}`
+pub(crate) fn parse_json(contents: &str) -> Result<HashMap<usize, u64>, String> {
+    let registers: HashMap<String, u64> =
+        serde_json::from_str(contents).map_err(|e| e.to_string())?;
+
+    registers
+        .into_iter()
+        .map(|(register, value)| Ok((parse_key(&register)?, value)))
+        .collect()
+}
+
+// `1 = 5` or `r2 = 3`, optionally inside a `[registers]` table
+pub(crate) fn parse_toml(contents: &str) -> Result<HashMap<usize, u64>, String> {
+    let table: toml::Table = toml::from_str(contents).map_err(|e| e.to_string())?;
+    let table = match table.get("registers") {
+        Some(toml::Value::Table(registers)) => registers,
+        _ => &table,
+    };
+
+    registers_from_toml_table(table)
+}
+
+// a TOML table with register addresses as keys, e.g. `{ r1 = 5, 2 = 3 }`
+pub(crate) fn registers_from_toml_table(
+    table: &toml::Table,
+) -> Result<HashMap<usize, u64>, String> {
+    table
+        .iter()
+        .map(|(register, value)| {
+            let value = value
+                .as_integer()
+                .and_then(|value| u64::try_from(value).ok())
+                .ok_or_else(|| {
+                    format!(
+                        "Value of register '{}' must be a non-negative integer",
+                        register
+                    )
+                })?;
+            Ok((parse_key(register)?, value))
+        })
+        .collect()
+}
+
+// `register,value` rows with an optional header row
+pub(crate) fn parse_csv(contents: &str) -> Result<HashMap<usize, u64>, String> {
+    let mut registers = HashMap::new();
+
+    for (line_number, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() || (line_number == 0 && line.trim() == "register,value") {
+            continue;
+        }
+
+        let mut parser = Parser::new(line);
+        let row = (|| {
+            parser.skip_whitespace();
+            let register = parser.parse_register()?;
+            parser.skip_whitespace();
+            parser.expect(',')?;
+            parser.skip_whitespace();
+            let value = parser.parse_value()?;
+            parser.skip_whitespace();
+            parser.expect_end()?;
+            Ok::<_, RegisterError>((register, value))
+        })()
+        .map_err(|e| format!("line {}: {}", line_number + 1, e))?;
+
+        registers.insert(row.0, row.1);
+    }
+
+    Ok(registers)
+}
+
+pub(crate) fn parse_key(key: &str) -> Result<usize, String> {
+    key.strip_prefix(['r', 'R'])
+        .unwrap_or(key)
+        .parse()
+        .map_err(|_| format!("Invalid register '{}'", key))
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, expected: &str) -> bool {
+        if self.input[self.position..].starts_with(expected) {
+            self.position += expected.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), RegisterError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected '{}'", expected)))
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), RegisterError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(c) => Err(self.error(format!("Unexpected character '{}'", c))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> RegisterError {
+        RegisterError::new(self.input, self.position, message)
+    }
+
+    fn parse_number<T: std::str::FromStr>(&mut self, what: &str) -> Result<T, RegisterError> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+
+        if start == self.position {
+            return Err(self.error(format!("Expected a {}", what)));
+        }
+
+        self.input[start..self.position].parse().map_err(|_| {
+            RegisterError::new(self.input, start, format!("The {} is too large", what))
+        })
+    }
+
+    fn parse_register(&mut self) -> Result<usize, RegisterError> {
+        let _ = self.eat('r') || self.eat('R');
+        self.parse_number("register address")
+    }
+
+    fn parse_value(&mut self) -> Result<u64, RegisterError> {
+        self.parse_number("register value")
+    }
+
+    fn parse_assignment_list(&mut self) -> Result<Vec<(usize, u64)>, RegisterError> {
+        let mut assignments = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            let start = self.position;
+            let first = self.parse_register()?;
+            let last = if self.eat_str("..=") {
+                self.parse_register()?
+            } else if self.eat_str("..") {
+                let position = self.position;
+                let end = self.parse_register()?;
+                end.checked_sub(1)
+                    .ok_or_else(|| RegisterError::new(self.input, position, "Range is empty"))?
+            } else {
+                first
+            };
+
+            self.skip_whitespace();
+            self.expect('=')?;
+            self.skip_whitespace();
+            let value = self.parse_value()?;
+
+            if last < first {
+                return Err(RegisterError::new(self.input, start, "Range is empty"));
+            }
+            if last - first >= MAX_RANGE_LENGTH {
+                return Err(RegisterError::new(
+                    self.input,
+                    start,
+                    format!("Range covers more than {} registers", MAX_RANGE_LENGTH),
+                ));
+            }
+
+            assignments.extend((first..=last).map(|register| (register, value)));
+
+            self.skip_whitespace();
+            if !self.eat(',') {
+                break;
+            }
+        }
+
+        Ok(assignments)
+    }
+
+    fn parse_pairs(&mut self) -> Result<Vec<(usize, u64)>, RegisterError> {
+        let mut assignments = Vec::new();
+
+        self.expect('[')?;
+        self.skip_whitespace();
+        if self.eat(']') {
+            return Ok(assignments);
+        }
+
+        loop {
+            self.skip_whitespace();
+            self.expect('(')?;
+            self.skip_whitespace();
+            let register = self.parse_register()?;
+            self.skip_whitespace();
+            self.expect(',')?;
+            self.skip_whitespace();
+            let value = self.parse_value()?;
+            self.skip_whitespace();
+            self.expect(')')?;
+            assignments.push((register, value));
+
+            self.skip_whitespace();
+            if !self.eat(',') {
+                break;
+            }
+        }
+
+        self.skip_whitespace();
+        self.expect(']')?;
+
+        Ok(assignments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(registers: HashMap<usize, u64>) -> Vec<(usize, u64)> {
+        let mut registers: Vec<_> = registers.into_iter().collect();
+        registers.sort_unstable();
+        registers
+    }
+
+    #[test]
+    fn assignments() {
+        assert_eq!(parse_assignments("").unwrap(), []);
+        assert_eq!(
+            parse_assignments(" 1=5, r2 = 3,R3=0 ").unwrap(),
+            [(1, 5), (2, 3), (3, 0)]
+        );
+        assert_eq!(
+            parse_assignments("10..=12=0,20..22=1").unwrap(),
+            [(10, 0), (11, 0), (12, 0), (20, 1), (21, 1)]
+        );
+    }
+
+    #[test]
+    fn legacy_pairs() {
+        assert_eq!(parse_assignments("[]").unwrap(), []);
+        assert_eq!(
+            parse_assignments("[(1,2), ( r2 , 4 )]").unwrap(),
+            [(1, 2), (2, 4)]
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_invalid_character() {
+        let cases = [
+            ("1=x", "Expected a register value", 2),
+            ("1=5;2=3", "Unexpected character ';'", 3),
+            ("1 5", "Expected '='", 2),
+            ("5..=3=0", "Range is empty", 0),
+            ("r1=5, 5..0=1", "Range is empty", 9),
+            (
+                "0..=1000000=0",
+                "Range covers more than 1000000 registers",
+                0,
+            ),
+            (
+                "1=99999999999999999999",
+                "The register value is too large",
+                2,
+            ),
+            ("[(1,2]", "Expected ')'", 5),
+            ("[(1,2)", "Expected ']'", 6),
+        ];
+
+        for (input, message, position) in cases {
+            let err = parse_assignments(input).unwrap_err();
+            assert_eq!(
+                (err.message.as_str(), err.position),
+                (message, position),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn caret_is_placed_below_the_error() {
+        let err = parse_assignments("1=5, 2=x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Expected a register value\n  1=5, 2=x\n         ^"
+        );
+
+        // the offset counts characters, not bytes
+        let err = parse_assignments("1=5 ä").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unexpected character 'ä'\n  1=5 ä\n      ^"
+        );
+    }
+
+    #[test]
+    fn arrays() {
+        assert_eq!(
+            parse_array("r10: 3, 1,4").unwrap(),
+            [(10, 3), (11, 1), (12, 4)]
+        );
+
+        let err = parse_array("10 3,1").unwrap_err();
+        assert_eq!((err.message.as_str(), err.position), ("Expected ':'", 3));
+        let err = parse_array(&format!("{}:1,2", usize::MAX)).unwrap_err();
+        assert_eq!(err.message, "Register address is too large");
+    }
+
+    #[test]
+    fn value_ranges() {
+        assert_eq!(
+            parse_value_ranges("r1=0..=10, r2 = 0..5,3=7").unwrap(),
+            [(1, 0..=10), (2, 0..=4), (3, 7..=7)]
+        );
+
+        let cases = [
+            ("r1=5..=3", "Range is empty", 3),
+            ("r1=0..0", "Range is empty", 6),
+            ("r1", "Expected '='", 2),
+            ("r1=0..=x", "Expected a register value", 7),
+        ];
+        for (input, message, position) in cases {
+            let err = parse_value_ranges(input).unwrap_err();
+            assert_eq!(
+                (err.message.as_str(), err.position),
+                (message, position),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn register_files() {
+        let expected = [(1, 5), (2, 3)];
+
+        assert_eq!(
+            sorted(parse_json(r#"{"1": 5, "r2": 3}"#).unwrap()),
+            expected
+        );
+        assert_eq!(sorted(parse_toml("1 = 5\nr2 = 3").unwrap()), expected);
+        assert_eq!(
+            sorted(parse_toml("[registers]\nR1 = 5\n2 = 3").unwrap()),
+            expected
+        );
+        assert_eq!(
+            sorted(parse_csv("register,value\n1,5\n\n r2 , 3\n").unwrap()),
+            expected
+        );
+
+        assert!(parse_json(r#"{"x": 5}"#).is_err());
+        assert_eq!(
+            parse_toml("r1 = -5").unwrap_err(),
+            "Value of register 'r1' must be a non-negative integer"
+        );
+        assert!(
+            parse_csv("1,5\n2;3")
+                .unwrap_err()
+                .starts_with("line 2: Expected ','")
+        );
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(parse_key("2"), Ok(2));
+        assert_eq!(parse_key("r2"), Ok(2));
+        assert_eq!(parse_key("R2"), Ok(2));
+        assert_eq!(parse_key("x2"), Err("Invalid register 'x2'".to_string()));
+    }
+}
@@ -0,0 +1,83 @@
+use arams_core::TestCase;
+use std::collections::HashMap;
+
+use crate::registers::registers_from_toml_table;
+
+// test cases are declared as an array of tables:
+//
+// [[test]]
+// name = "factorial of 5"
+// inputs = { r1 = 5 }
+// accumulator = 120
+// registers = { r2 = 120 }
+// max_steps = 200
+pub(crate) fn parse_toml(contents: &str) -> Result<Vec<TestCase>, String> {
+    let table: toml::Table = toml::from_str(contents).map_err(|e| e.to_string())?;
+
+    test_cases_from_toml_table(&table)
+}
+
+pub(crate) fn test_cases_from_toml_table(table: &toml::Table) -> Result<Vec<TestCase>, String> {
+    let Some(test_cases) = table.get("test") else {
+        return Ok(Vec::new());
+    };
+    let test_cases = test_cases
+        .as_array()
+        .ok_or("'test' must be an array of tables, declared with [[test]]")?;
+
+    test_cases
+        .iter()
+        .enumerate()
+        .map(|(index, test_case)| {
+            let test_case = test_case
+                .as_table()
+                .ok_or_else(|| format!("test {} must be a table", index + 1))?;
+            test_case_from_toml_table(test_case, index)
+                .map_err(|e| format!("test {}: {}", index + 1, e))
+        })
+        .collect()
+}
+
+fn test_case_from_toml_table(table: &toml::Table, index: usize) -> Result<TestCase, String> {
+    for key in table.keys() {
+        if !["name", "inputs", "accumulator", "registers", "max_steps"].contains(&key.as_str()) {
+            return Err(format!("unknown key '{}'", key));
+        }
+    }
+
+    let name = match table.get("name") {
+        Some(name) => name.as_str().ok_or("'name' must be a string")?.to_string(),
+        None => format!("case {}", index + 1),
+    };
+
+    Ok(TestCase::new(
+        name,
+        registers(table, "inputs")?,
+        unsigned(table, "accumulator")?,
+        registers(table, "registers")?,
+        unsigned(table, "max_steps")?,
+    ))
+}
+
+fn registers(table: &toml::Table, key: &str) -> Result<HashMap<usize, u64>, String> {
+    match table.get(key) {
+        Some(registers) => registers_from_toml_table(
+            registers
+                .as_table()
+                .ok_or_else(|| format!("'{}' must be a table", key))?,
+        ),
+        None => Ok(HashMap::new()),
+    }
+}
+
+fn unsigned(table: &toml::Table, key: &str) -> Result<Option<u64>, String> {
+    table
+        .get(key)
+        .map(|value| {
+            value
+                .as_integer()
+                .and_then(|value| u64::try_from(value).ok())
+                .ok_or_else(|| format!("'{}' must be a non-negative integer", key))
+        })
+        .transpose()
+}
@@ -0,0 +1,269 @@
+use arams_core::{Machine, Program, step};
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Row, Table},
+};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::errors::Error;
+
+// steps per second
+const SPEEDS: [u64; 9] = [1, 2, 5, 10, 20, 50, 100, 500, 1000];
+const DEFAULT_SPEED: usize = 2;
+// registers changed within this many steps are highlighted
+const CHANGE_HIGHLIGHT_STEPS: u64 = 5;
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+const KEYS: &str =
+    "s/→ step  r run  p/space pause  +/- speed  ↑/↓ scroll registers  R reset  q quit";
+
+pub(crate) fn tui(
+    program: Program,
+    source: Vec<String>,
+    registers: HashMap<usize, u64>,
+) -> Result<(), Error> {
+    let mut terminal = ratatui::try_init().map_err(Error::IOFailedToDrawTerminal)?;
+    let result = App::new(program, source, registers).run(&mut terminal);
+    ratatui::try_restore().map_err(Error::IOFailedToDrawTerminal)?;
+
+    result
+}
+
+struct App {
+    program: Program,
+    source: Vec<String>,
+    initial_registers: HashMap<usize, u64>,
+    machine: Machine,
+    steps: u64,
+    changed_at: HashMap<usize, u64>,
+    error: Option<String>,
+    running: bool,
+    speed: usize,
+    register_scroll: usize,
+    quit: bool,
+}
+
+impl App {
+    fn new(program: Program, source: Vec<String>, registers: HashMap<usize, u64>) -> Self {
+        Self {
+            program,
+            source,
+            machine: Machine::new_with_preseeded_registers(registers.clone()),
+            initial_registers: registers,
+            steps: 0,
+            changed_at: HashMap::new(),
+            error: None,
+            running: false,
+            speed: DEFAULT_SPEED,
+            register_scroll: 0,
+            quit: false,
+        }
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> Result<(), Error> {
+        let mut last_step = Instant::now();
+
+        while !self.quit {
+            terminal
+                .draw(|frame| self.draw(frame))
+                .map_err(Error::IOFailedToDrawTerminal)?;
+
+            let step_interval = Duration::from_secs(1) / SPEEDS[self.speed] as u32;
+            let timeout = if self.running {
+                step_interval
+                    .saturating_sub(last_step.elapsed())
+                    .min(FRAME_INTERVAL)
+            } else {
+                Duration::from_millis(250)
+            };
+
+            if event::poll(timeout).map_err(Error::IOFailedToDrawTerminal)?
+                && let Event::Key(key) = event::read().map_err(Error::IOFailedToDrawTerminal)?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key.code);
+            }
+
+            if self.running {
+                // fast speeds execute multiple steps per frame
+                let due = (last_step.elapsed().as_nanos() / step_interval.as_nanos()) as u64;
+                for _ in 0..due {
+                    if !self.running {
+                        break;
+                    }
+                    self.step();
+                }
+                if due > 0 {
+                    last_step = Instant::now();
+                }
+            } else {
+                last_step = Instant::now();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('s') | KeyCode::Right => {
+                self.running = false;
+                self.step();
+            }
+            KeyCode::Char('r') => self.running = !self.machine.is_stopped(),
+            KeyCode::Char('p') | KeyCode::Char(' ') => {
+                self.running = !self.running && !self.machine.is_stopped();
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+            }
+            KeyCode::Char('-') => self.speed = self.speed.saturating_sub(1),
+            KeyCode::Up => self.register_scroll = self.register_scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll_registers_down(1),
+            KeyCode::PageUp => self.register_scroll = self.register_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll_registers_down(10),
+            KeyCode::Char('R') => {
+                self.machine =
+                    Machine::new_with_preseeded_registers(self.initial_registers.clone());
+                self.steps = 0;
+                self.changed_at.clear();
+                self.error = None;
+                self.running = false;
+            }
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            _ => {}
+        }
+    }
+
+    fn scroll_registers_down(&mut self, rows: usize) {
+        let last_register = self.machine.get_registers().len().saturating_sub(1);
+        self.register_scroll = (self.register_scroll + rows).min(last_register);
+    }
+
+    fn step(&mut self) {
+        if self.machine.is_stopped() {
+            self.running = false;
+            return;
+        }
+
+        let registers = self.machine.get_registers().clone();
+        if let Err(err) = step(&self.program, &mut self.machine) {
+            self.error = Some(err.to_string());
+            self.running = false;
+            return;
+        }
+        self.steps += 1;
+
+        for (register, value) in self.machine.get_registers() {
+            if registers.get(register) != Some(value) {
+                self.changed_at.insert(*register, self.steps);
+            }
+        }
+
+        if self.machine.is_stopped() {
+            self.running = false;
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [source, side] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(main);
+        let [status, registers] =
+            Layout::vertical([Constraint::Length(7), Constraint::Min(0)]).areas(side);
+
+        self.draw_source(frame, source);
+        self.draw_status(frame, status);
+        self.draw_registers(frame, registers);
+        frame.render_widget(Paragraph::new(KEYS).dim(), footer);
+    }
+
+    fn draw_source(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let items: Vec<ListItem> = self
+            .source
+            .iter()
+            .enumerate()
+            .map(|(index, line)| ListItem::new(format!("{:>4} {}", index + 1, line)))
+            .collect();
+
+        let current_line = if self.machine.is_stopped() {
+            None
+        } else {
+            self.program
+                .source_line(self.machine.get_program_counter())
+                .map(|line| line - 1)
+        };
+
+        let list = List::new(items)
+            .block(Block::bordered().title(" Source "))
+            .highlight_style(Style::new().reversed().bold());
+        let mut state = ListState::default().with_selected(current_line);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let state = if self.error.is_some() {
+            "error".red()
+        } else if self.machine.is_stopped() {
+            "stopped".green()
+        } else if self.running {
+            "running".yellow()
+        } else {
+            "paused".blue()
+        };
+
+        let mut lines = vec![
+            Line::from(vec![
+                "Accumulator: ".into(),
+                self.machine.get_accumulator().to_string().bold(),
+            ]),
+            Line::from(format!(
+                "Program counter: {}",
+                self.machine.get_program_counter()
+            )),
+            Line::from(format!("Steps: {}", self.steps)),
+            Line::from(vec!["State: ".into(), state]),
+            Line::from(format!("Speed: {} steps/s", SPEEDS[self.speed])),
+        ];
+        if let Some(error) = &self.error {
+            lines.push(Line::from(Span::from(error.as_str()).red()));
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(" Machine ")),
+            area,
+        );
+    }
+
+    fn draw_registers(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let mut registers: Vec<_> = self.machine.get_registers().iter().collect();
+        registers.sort_by_key(|(register, _)| *register);
+
+        let rows: Vec<Row> = registers
+            .into_iter()
+            .skip(self.register_scroll)
+            .map(|(register, value)| {
+                let row = Row::new(vec![format!("r{}", register), value.to_string()]);
+                match self.changed_at.get(register) {
+                    Some(&step) if step == self.steps => row.yellow().bold(),
+                    Some(&step) if self.steps - step < CHANGE_HIGHLIGHT_STEPS => row.yellow(),
+                    _ => row,
+                }
+            })
+            .collect();
+
+        let table = Table::new(rows, [Constraint::Length(8), Constraint::Min(0)])
+            .header(Row::new(vec!["Register", "Value"]).underlined())
+            .block(Block::bordered().title(" Registers "));
+        frame.render_widget(table, area);
+    }
+}
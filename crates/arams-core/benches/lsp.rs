@@ -0,0 +1,66 @@
+use arams_core::lsp::{Document, Position, Range, TextEdit, analyze};
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+const FACTORIAL_PROGRAM: &str = include_str!("../../../examples/factorial.txt");
+
+fn bench_analyze(c: &mut Criterion) {
+    c.bench_function("analyze factorial", |b| {
+        b.iter(|| black_box(analyze(black_box(FACTORIAL_PROGRAM))))
+    });
+}
+
+// 10000 lines of labelled blocks that jump to each other
+fn large_program() -> String {
+    let mut program = String::new();
+    for block in 0..2000 {
+        program.push_str(&format!("block{}: load {}\n", block, block % 16));
+        program.push_str("    add #1 // next value\n");
+        program.push_str(&format!("    store *{}\n", block % 16));
+        program.push_str(&format!("    jzero block{}\n", (block + 1) % 2000));
+        program.push_str(&format!("    goto block{}\n", block / 2));
+    }
+    program
+}
+
+fn bench_large_document(c: &mut Criterion) {
+    let program = large_program();
+    assert_eq!(program.lines().count(), 10_000);
+
+    c.bench_function("analyze 10k lines", |b| {
+        b.iter(|| black_box(analyze(black_box(program.as_str()))))
+    });
+
+    // typing a character in the middle of the program, then removing it again
+    let document = Document::new(program.as_str());
+    let position = Position::new(5_001, 8);
+    let insert = TextEdit::new(Range::new(position, position), "1");
+    let remove = TextEdit::new(Range::new(position, Position::new(5_001, 9)), "");
+    c.bench_function("edit 10k lines document", |b| {
+        b.iter_batched_ref(
+            || document.clone(),
+            |document| {
+                document.edit(&insert);
+                document.edit(&remove);
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+
+    // inserting a line moves every token below it
+    let newline = TextEdit::new(Range::new(position, position), "\n");
+    c.bench_function("insert line into 10k lines document", |b| {
+        b.iter_batched_ref(
+            || document.clone(),
+            |document| document.edit(&newline),
+            criterion::BatchSize::LargeInput,
+        )
+    });
+
+    c.bench_function("diagnostics of 10k lines document", |b| {
+        b.iter(|| black_box(document.diagnostics()))
+    });
+}
+
+criterion_group!(benches, bench_analyze, bench_large_document);
+criterion_main!(benches);
@@ -0,0 +1,438 @@
+use std::collections::HashMap;
+
+use crate::{
+    errors::BytecodeError,
+    program::{Address, Instruction, Operand, Program},
+    testing::TestCase,
+};
+
+pub const MAGIC: &[u8; 4] = b"RAMC";
+pub const VERSION: u16 = 1;
+
+// sections follow the instruction stream as `tag (u8) | length (u32) | payload`,
+// unknown sections are skipped when decoding
+const SECTION_LABELS: u8 = 1;
+const SECTION_SOURCE_LINES: u8 = 2;
+const SECTION_INPUTS: u8 = 3;
+const SECTION_TEST_CASES: u8 = 4;
+
+// opcode (u8) | argument kind (u8) | argument (u64)
+const INSTRUCTION_SIZE: usize = 10;
+
+const OPCODE_LOAD: u8 = 0;
+const OPCODE_STORE: u8 = 1;
+const OPCODE_ADD: u8 = 2;
+const OPCODE_SUB: u8 = 3;
+const OPCODE_MUL: u8 = 4;
+const OPCODE_DIV: u8 = 5;
+const OPCODE_GOTO: u8 = 6;
+const OPCODE_JZERO: u8 = 7;
+const OPCODE_JNZERO: u8 = 8;
+const OPCODE_END: u8 = 9;
+
+const ARGUMENT_NONE: u8 = 0;
+const ARGUMENT_IMMEDIATE: u8 = 1;
+const ARGUMENT_DIRECT: u8 = 2;
+const ARGUMENT_INDIRECT: u8 = 3;
+const ARGUMENT_JUMP_TARGET: u8 = 4;
+
+pub fn is_bytecode(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+pub fn encode(program: &Program, include_debug_info: bool) -> Result<Vec<u8>, BytecodeError> {
+    let mut bytes = Vec::new();
+
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(
+        &to_u32(program.instructions().len(), "number of instructions")?.to_le_bytes(),
+    );
+
+    for instruction in program.instructions() {
+        let (opcode, argument_kind, argument) = match instruction {
+            Instruction::Load(operand) => {
+                (OPCODE_LOAD, operand_kind(operand), operand_value(operand))
+            }
+            Instruction::Store(address) => match address {
+                Address::Direct(address) => (OPCODE_STORE, ARGUMENT_DIRECT, *address as u64),
+                Address::Indirect(address) => (OPCODE_STORE, ARGUMENT_INDIRECT, *address as u64),
+            },
+            Instruction::Add(operand) => {
+                (OPCODE_ADD, operand_kind(operand), operand_value(operand))
+            }
+            Instruction::Sub(operand) => {
+                (OPCODE_SUB, operand_kind(operand), operand_value(operand))
+            }
+            Instruction::Mul(operand) => {
+                (OPCODE_MUL, operand_kind(operand), operand_value(operand))
+            }
+            Instruction::Div(operand) => {
+                (OPCODE_DIV, operand_kind(operand), operand_value(operand))
+            }
+            Instruction::Goto(label) => (
+                OPCODE_GOTO,
+                ARGUMENT_JUMP_TARGET,
+                jump_target(program, label)?,
+            ),
+            Instruction::Jzero(label) => (
+                OPCODE_JZERO,
+                ARGUMENT_JUMP_TARGET,
+                jump_target(program, label)?,
+            ),
+            Instruction::Jnzero(label) => (
+                OPCODE_JNZERO,
+                ARGUMENT_JUMP_TARGET,
+                jump_target(program, label)?,
+            ),
+            Instruction::End => (OPCODE_END, ARGUMENT_NONE, 0),
+        };
+
+        bytes.push(opcode);
+        bytes.push(argument_kind);
+        bytes.extend_from_slice(&argument.to_le_bytes());
+    }
+
+    // inputs change the behaviour of a program, they are no debug information
+    if !program.inputs().is_empty() {
+        let mut payload = Vec::new();
+        push_registers(&mut payload, program.inputs(), "number of inputs")?;
+
+        push_section(&mut bytes, SECTION_INPUTS, &payload)?;
+    }
+
+    // so are test cases, a compiled program can be tested like its source
+    if !program.test_cases().is_empty() {
+        let mut payload = Vec::new();
+        payload.extend_from_slice(
+            &to_u32(program.test_cases().len(), "number of test cases")?.to_le_bytes(),
+        );
+        for test_case in program.test_cases() {
+            push_test_case(&mut payload, test_case)?;
+        }
+
+        push_section(&mut bytes, SECTION_TEST_CASES, &payload)?;
+    }
+
+    if include_debug_info {
+        let mut labels: Vec<_> = program.labels().iter().collect();
+        labels.sort_by(|(a_label, a_index), (b_label, b_index)| {
+            a_index.cmp(b_index).then(a_label.cmp(b_label))
+        });
+
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&to_u32(labels.len(), "number of labels")?.to_le_bytes());
+        for (label, &index) in labels {
+            payload.extend_from_slice(&to_u32(index, "label index")?.to_le_bytes());
+            let length =
+                u16::try_from(label.len()).map_err(|_| BytecodeError::TooLarge("label"))?;
+            payload.extend_from_slice(&length.to_le_bytes());
+            payload.extend_from_slice(label.as_bytes());
+        }
+
+        push_section(&mut bytes, SECTION_LABELS, &payload)?;
+
+        if program.source_lines().len() == program.instructions().len() {
+            let mut payload = Vec::new();
+            for &line_number in program.source_lines() {
+                payload.extend_from_slice(&to_u32(line_number, "source line")?.to_le_bytes());
+            }
+
+            push_section(&mut bytes, SECTION_SOURCE_LINES, &payload)?;
+        }
+    }
+
+    Ok(bytes)
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Program, BytecodeError> {
+    let mut reader = Reader::new(bytes);
+
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(BytecodeError::InvalidMagic);
+    }
+
+    let version = reader.u16()?;
+    if version != VERSION {
+        return Err(BytecodeError::UnsupportedVersion(version));
+    }
+
+    let instruction_count = reader.u32()? as usize;
+    // the count is not trusted before the instructions are known to be there
+    if instruction_count
+        .checked_mul(INSTRUCTION_SIZE)
+        .is_none_or(|size| size > reader.remaining())
+    {
+        return Err(BytecodeError::UnexpectedEnd);
+    }
+    let mut raw_instructions = Vec::with_capacity(instruction_count);
+    for _ in 0..instruction_count {
+        raw_instructions.push((reader.u8()?, reader.u8()?, reader.u64()?));
+    }
+
+    let mut labels: HashMap<String, usize> = HashMap::new();
+    let mut source_lines = Vec::new();
+    let mut inputs = HashMap::new();
+    let mut test_cases = Vec::new();
+    while !reader.is_empty() {
+        let tag = reader.u8()?;
+        let length = reader.u32()? as usize;
+        let mut section = Reader::new(reader.take(length)?);
+
+        if tag == SECTION_LABELS {
+            for _ in 0..section.u32()? {
+                let index = section.u32()? as usize;
+                if index > instruction_count {
+                    return Err(BytecodeError::InvalidJumpTarget(index as u64));
+                }
+                let length = section.u16()? as usize;
+                let label = std::str::from_utf8(section.take(length)?)
+                    .map_err(|_| BytecodeError::InvalidLabel)?;
+                labels.insert(label.to_string(), index);
+            }
+        } else if tag == SECTION_SOURCE_LINES {
+            while !section.is_empty() {
+                source_lines.push(section.u32()? as usize);
+            }
+        } else if tag == SECTION_INPUTS {
+            inputs = read_registers(&mut section)?;
+        } else if tag == SECTION_TEST_CASES {
+            for _ in 0..section.u32()? {
+                test_cases.push(read_test_case(&mut section)?);
+            }
+        }
+    }
+
+    // like in `encode`, the table covers every instruction or is left out
+    if !source_lines.is_empty() && source_lines.len() != instruction_count {
+        return Err(BytecodeError::InvalidSourceLines);
+    }
+
+    let mut label_names: HashMap<usize, String> = HashMap::new();
+    for (label, &index) in &labels {
+        let name = label_names.entry(index).or_insert_with(|| label.clone());
+        if label < name {
+            *name = label.clone();
+        }
+    }
+
+    let mut instructions = Vec::with_capacity(instruction_count);
+    for (opcode, argument_kind, argument) in raw_instructions {
+        let mut jump_label = || {
+            if argument_kind != ARGUMENT_JUMP_TARGET {
+                return Err(BytecodeError::InvalidArgument(argument_kind));
+            }
+            let target = usize::try_from(argument)
+                .ok()
+                .filter(|&target| target <= instruction_count)
+                .ok_or(BytecodeError::InvalidJumpTarget(argument))?;
+            // programs without a label table get generated labels for every jump target
+            Ok(label_names
+                .entry(target)
+                .or_insert_with(|| {
+                    let label = format!("L{}", target);
+                    labels.insert(label.clone(), target);
+                    label
+                })
+                .clone())
+        };
+
+        instructions.push(match opcode {
+            OPCODE_LOAD => Instruction::Load(operand(argument_kind, argument)?),
+            OPCODE_STORE => Instruction::Store(match operand(argument_kind, argument)? {
+                Operand::DirectAddress(address) => Address::Direct(address),
+                Operand::IndirectAddress(address) => Address::Indirect(address),
+                Operand::Immediate(_) => return Err(BytecodeError::InvalidArgument(argument_kind)),
+            }),
+            OPCODE_ADD => Instruction::Add(operand(argument_kind, argument)?),
+            OPCODE_SUB => Instruction::Sub(operand(argument_kind, argument)?),
+            OPCODE_MUL => Instruction::Mul(operand(argument_kind, argument)?),
+            OPCODE_DIV => Instruction::Div(operand(argument_kind, argument)?),
+            OPCODE_GOTO => Instruction::Goto(jump_label()?),
+            OPCODE_JZERO => Instruction::Jzero(jump_label()?),
+            OPCODE_JNZERO => Instruction::Jnzero(jump_label()?),
+            OPCODE_END if argument_kind == ARGUMENT_NONE => Instruction::End,
+            OPCODE_END => return Err(BytecodeError::InvalidArgument(argument_kind)),
+            _ => return Err(BytecodeError::InvalidOpcode(opcode)),
+        });
+    }
+
+    Ok(Program::new(instructions, labels, source_lines)
+        .with_inputs(inputs)
+        .with_test_cases(test_cases))
+}
+
+// count (u32) | register (u64) | value (u64) ..., sorted by register
+fn push_registers(
+    payload: &mut Vec<u8>,
+    registers: &HashMap<usize, u64>,
+    what: &'static str,
+) -> Result<(), BytecodeError> {
+    let mut registers: Vec<_> = registers.iter().collect();
+    registers.sort_by_key(|(register, _)| *register);
+
+    payload.extend_from_slice(&to_u32(registers.len(), what)?.to_le_bytes());
+    for (&register, &value) in registers {
+        payload.extend_from_slice(&(register as u64).to_le_bytes());
+        payload.extend_from_slice(&value.to_le_bytes());
+    }
+
+    Ok(())
+}
+
+fn read_registers(section: &mut Reader) -> Result<HashMap<usize, u64>, BytecodeError> {
+    let mut registers = HashMap::new();
+    for _ in 0..section.u32()? {
+        let register = usize::try_from(section.u64()?)
+            .map_err(|_| BytecodeError::InvalidArgument(ARGUMENT_DIRECT))?;
+        registers.insert(register, section.u64()?);
+    }
+
+    Ok(registers)
+}
+
+// name length (u16) | name | inputs | expected accumulator | expected registers | max steps,
+// the optional values are prefixed with a presence flag (u8)
+fn push_test_case(payload: &mut Vec<u8>, test_case: &TestCase) -> Result<(), BytecodeError> {
+    let name = test_case.name().as_bytes();
+    let length = u16::try_from(name.len()).map_err(|_| BytecodeError::TooLarge("test name"))?;
+    payload.extend_from_slice(&length.to_le_bytes());
+    payload.extend_from_slice(name);
+
+    push_registers(payload, test_case.inputs(), "number of test inputs")?;
+    push_optional(payload, test_case.expected_accumulator());
+    push_registers(
+        payload,
+        test_case.expected_registers(),
+        "number of expected registers",
+    )?;
+    push_optional(payload, test_case.max_steps());
+
+    Ok(())
+}
+
+fn read_test_case(section: &mut Reader) -> Result<TestCase, BytecodeError> {
+    let length = section.u16()? as usize;
+    let name = std::str::from_utf8(section.take(length)?)
+        .map_err(|_| BytecodeError::InvalidTestCase)?
+        .to_string();
+    let inputs = read_registers(section)?;
+    let expected_accumulator = read_optional(section)?;
+    let expected_registers = read_registers(section)?;
+    let max_steps = read_optional(section)?;
+
+    Ok(TestCase::new(
+        name,
+        inputs,
+        expected_accumulator,
+        expected_registers,
+        max_steps,
+    ))
+}
+
+fn push_optional(payload: &mut Vec<u8>, value: Option<u64>) {
+    match value {
+        Some(value) => {
+            payload.push(1);
+            payload.extend_from_slice(&value.to_le_bytes());
+        }
+        None => payload.push(0),
+    }
+}
+
+fn read_optional(section: &mut Reader) -> Result<Option<u64>, BytecodeError> {
+    match section.u8()? {
+        0 => Ok(None),
+        1 => Ok(Some(section.u64()?)),
+        _ => Err(BytecodeError::InvalidTestCase),
+    }
+}
+
+fn operand_kind(operand: &Operand) -> u8 {
+    match operand {
+        Operand::Immediate(_) => ARGUMENT_IMMEDIATE,
+        Operand::DirectAddress(_) => ARGUMENT_DIRECT,
+        Operand::IndirectAddress(_) => ARGUMENT_INDIRECT,
+    }
+}
+
+fn operand_value(operand: &Operand) -> u64 {
+    match operand {
+        Operand::Immediate(value) => *value,
+        Operand::DirectAddress(address) | Operand::IndirectAddress(address) => *address as u64,
+    }
+}
+
+fn operand(argument_kind: u8, argument: u64) -> Result<Operand, BytecodeError> {
+    let address =
+        || usize::try_from(argument).map_err(|_| BytecodeError::InvalidArgument(argument_kind));
+
+    match argument_kind {
+        ARGUMENT_IMMEDIATE => Ok(Operand::Immediate(argument)),
+        ARGUMENT_DIRECT => Ok(Operand::DirectAddress(address()?)),
+        ARGUMENT_INDIRECT => Ok(Operand::IndirectAddress(address()?)),
+        _ => Err(BytecodeError::InvalidArgument(argument_kind)),
+    }
+}
+
+fn jump_target(program: &Program, label: &str) -> Result<u64, BytecodeError> {
+    program
+        .labels()
+        .get(label)
+        .map(|&index| index as u64)
+        .ok_or_else(|| BytecodeError::UnknownLabel(label.to_string()))
+}
+
+fn push_section(bytes: &mut Vec<u8>, tag: u8, payload: &[u8]) -> Result<(), BytecodeError> {
+    bytes.push(tag);
+    bytes.extend_from_slice(&to_u32(payload.len(), "section")?.to_le_bytes());
+    bytes.extend_from_slice(payload);
+    Ok(())
+}
+
+fn to_u32(value: usize, what: &'static str) -> Result<u32, BytecodeError> {
+    u32::try_from(value).map_err(|_| BytecodeError::TooLarge(what))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], BytecodeError> {
+        if self.bytes.len() < length {
+            return Err(BytecodeError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, BytecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, BytecodeError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, BytecodeError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, BytecodeError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use crate::testing::TestCase;
+
+// `@input r1 = 5, r2 = 3` declares default values of registers
+pub(crate) fn input_directive_from_comment(comment: &str) -> Option<Result<Vec<(usize, u64)>, ()>> {
+    let assignments = directive_arguments(comment, "@input")?;
+
+    Some(assignments_from_str(assignments))
+}
+
+// `@test name: r1 = 5 -> acc = 120, r2 = 120; max_steps = 100` declares a test case
+pub(crate) fn test_directive_from_comment(comment: &str) -> Option<Result<TestCase, ()>> {
+    let arguments = directive_arguments(comment, "@test")?;
+
+    Some((|| {
+        let (name, rest) = arguments.split_once(':').ok_or(())?;
+        let (rest, options) = match rest.split_once(';') {
+            Some((rest, options)) => (rest, Some(options)),
+            None => (rest, None),
+        };
+        let (inputs, expectations) = rest.split_once("->").ok_or(())?;
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(());
+        }
+
+        let inputs = match inputs.trim() {
+            "" => HashMap::new(),
+            inputs => assignments_from_str(inputs)?.into_iter().collect(),
+        };
+
+        let mut expected_accumulator = None;
+        let mut expected_registers = HashMap::new();
+        for expectation in expectations.split(',') {
+            let (target, value) = expectation.split_once('=').ok_or(())?;
+            let value = value.trim().parse::<u64>().map_err(|_| ())?;
+            match target.trim() {
+                "acc" | "accumulator" => expected_accumulator = Some(value),
+                register => {
+                    expected_registers.insert(register_from_str(register)?, value);
+                }
+            }
+        }
+
+        let max_steps = match options {
+            Some(options) => {
+                let (option, value) = options.split_once('=').ok_or(())?;
+                if option.trim() != "max_steps" {
+                    return Err(());
+                }
+                Some(value.trim().parse::<u64>().map_err(|_| ())?)
+            }
+            None => None,
+        };
+
+        Ok(TestCase::new(
+            name.to_string(),
+            inputs,
+            expected_accumulator,
+            expected_registers,
+            max_steps,
+        ))
+    })())
+}
+
+// whether the comment is a directive that does not compile, for the diagnostics of the editor
+pub(crate) fn is_invalid_directive(comment: &str) -> bool {
+    matches!(input_directive_from_comment(comment), Some(Err(())))
+        || matches!(test_directive_from_comment(comment), Some(Err(())))
+}
+
+// the arguments of a directive, `None` if the comment is not the given directive
+fn directive_arguments<'a>(comment: &'a str, directive: &str) -> Option<&'a str> {
+    let arguments = comment.strip_prefix(directive)?;
+    if !arguments.is_empty() && !arguments.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    Some(arguments)
+}
+
+fn assignments_from_str(assignments: &str) -> Result<Vec<(usize, u64)>, ()> {
+    assignments
+        .split(',')
+        .map(|assignment| {
+            let (register, value) = assignment.split_once('=').ok_or(())?;
+            let value = value.trim().parse::<u64>().map_err(|_| ())?;
+            Ok((register_from_str(register)?, value))
+        })
+        .collect()
+}
+
+fn register_from_str(register: &str) -> Result<usize, ()> {
+    let register = register.trim();
+    register
+        .strip_prefix(['r', 'R'])
+        .unwrap_or(register)
+        .parse::<usize>()
+        .map_err(|_| ())
+}
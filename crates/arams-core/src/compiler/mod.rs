@@ -1,3 +1,4 @@
+pub(crate) mod directives;
 mod parser;
 mod source_code;
 mod token;
@@ -20,7 +20,7 @@ pub(crate) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<CompileError>> {
                     value,
                 }) => match operand_from_str(value) {
                     Ok(operand) => {
-                        program_builder.add_instruction(Instruction::Load(operand));
+                        program_builder.add_instruction(Instruction::Load(operand), *line_number);
                     }
                     Err(_) => {
                         errors.push(CompileError::new(
@@ -44,7 +44,7 @@ pub(crate) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<CompileError>> {
                     value,
                 }) => match address_from_str(value) {
                     Ok(address) => {
-                        program_builder.add_instruction(Instruction::Store(address));
+                        program_builder.add_instruction(Instruction::Store(address), *line_number);
                     }
                     Err(_) => {
                         errors.push(CompileError::new(
@@ -68,7 +68,7 @@ pub(crate) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<CompileError>> {
                     value,
                 }) => match operand_from_str(value) {
                     Ok(operand) => {
-                        program_builder.add_instruction(Instruction::Add(operand));
+                        program_builder.add_instruction(Instruction::Add(operand), *line_number);
                     }
                     Err(_) => {
                         errors.push(CompileError::new(
@@ -92,7 +92,7 @@ pub(crate) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<CompileError>> {
                     value,
                 }) => match operand_from_str(value) {
                     Ok(operand) => {
-                        program_builder.add_instruction(Instruction::Sub(operand));
+                        program_builder.add_instruction(Instruction::Sub(operand), *line_number);
                     }
                     Err(_) => {
                         errors.push(CompileError::new(
@@ -116,7 +116,7 @@ pub(crate) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<CompileError>> {
                     value,
                 }) => match operand_from_str(value) {
                     Ok(operand) => {
-                        program_builder.add_instruction(Instruction::Mul(operand));
+                        program_builder.add_instruction(Instruction::Mul(operand), *line_number);
                     }
                     Err(_) => {
                         errors.push(CompileError::new(
@@ -140,7 +140,7 @@ pub(crate) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<CompileError>> {
                     value,
                 }) => match operand_from_str(value) {
                     Ok(operand) => {
-                        program_builder.add_instruction(Instruction::Div(operand));
+                        program_builder.add_instruction(Instruction::Div(operand), *line_number);
                     }
                     Err(_) => {
                         errors.push(CompileError::new(
@@ -163,7 +163,8 @@ pub(crate) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<CompileError>> {
                     line_number: _,
                     value,
                 }) => {
-                    program_builder.add_instruction(Instruction::Goto(value.to_string()));
+                    program_builder
+                        .add_instruction(Instruction::Goto(value.to_string()), *line_number);
                 }
                 _ => {
                     errors.push(CompileError::new(
@@ -178,7 +179,8 @@ pub(crate) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<CompileError>> {
                     line_number: _,
                     value,
                 }) => {
-                    program_builder.add_instruction(Instruction::Jzero(value.to_string()));
+                    program_builder
+                        .add_instruction(Instruction::Jzero(value.to_string()), *line_number);
                 }
                 _ => {
                     errors.push(CompileError::new(
@@ -193,7 +195,8 @@ pub(crate) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<CompileError>> {
                     line_number: _,
                     value,
                 }) => {
-                    program_builder.add_instruction(Instruction::Jnzero(value.to_string()));
+                    program_builder
+                        .add_instruction(Instruction::Jnzero(value.to_string()), *line_number);
                 }
                 _ => {
                     errors.push(CompileError::new(
@@ -203,8 +206,8 @@ pub(crate) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<CompileError>> {
                     ));
                 }
             },
-            Token::End { line_number: _ } => {
-                program_builder.add_instruction(Instruction::End);
+            Token::End { line_number } => {
+                program_builder.add_instruction(Instruction::End, *line_number);
             }
             Token::LabelDefinition { line_number, value } => {
                 if program_builder.label_exists(value) {
@@ -217,6 +220,19 @@ pub(crate) fn parse(tokens: Vec<Token>) -> Result<Program, Vec<CompileError>> {
                 }
                 program_builder.add_label(value.to_string());
             }
+            Token::Input {
+                line_number: _,
+                register,
+                value,
+            } => {
+                program_builder.add_input(*register, *value);
+            }
+            Token::Test {
+                line_number: _,
+                test_case,
+            } => {
+                program_builder.add_test_case(test_case.clone());
+            }
             Token::Argument { line_number, value } => {
                 // all arguments are handled at the instructions that require them
                 errors.push(CompileError::new(
@@ -1,4 +1,5 @@
 use crate::{
+    compiler::directives::{input_directive_from_comment, test_directive_from_comment},
     compiler::source_code::IntoSourceCode,
     compiler::token::Token,
     errors::{CompileError, CompileErrorKind},
@@ -66,7 +67,45 @@ pub fn tokenize(source_code: impl IntoSourceCode) -> Result<Vec<Token>, Vec<Comp
                     });
                 }
                 _ if word.starts_with("//") => {
-                    let comment = words_iter.collect::<Vec<&str>>().join(" ");
+                    // text directly after the slashes (e.g. `//@input`) is part of the comment
+                    let comment = std::iter::once(&word[2..])
+                        .chain(words_iter)
+                        .filter(|word| !word.is_empty())
+                        .collect::<Vec<&str>>()
+                        .join(" ");
+                    match input_directive_from_comment(&comment) {
+                        Some(Ok(inputs)) => {
+                            tokens.extend(inputs.into_iter().map(|(register, value)| {
+                                Token::Input {
+                                    line_number: line_number + 1,
+                                    register,
+                                    value,
+                                }
+                            }));
+                        }
+                        Some(Err(())) => {
+                            errors.push(CompileError::new(
+                                line_number + 1,
+                                CompileErrorKind::InvalidDirective,
+                                comment.clone(),
+                            ));
+                        }
+                        None => {}
+                    }
+                    match test_directive_from_comment(&comment) {
+                        Some(Ok(test_case)) => tokens.push(Token::Test {
+                            line_number: line_number + 1,
+                            test_case,
+                        }),
+                        Some(Err(())) => {
+                            errors.push(CompileError::new(
+                                line_number + 1,
+                                CompileErrorKind::InvalidDirective,
+                                comment.clone(),
+                            ));
+                        }
+                        None => {}
+                    }
                     tokens.push(Token::Comment {
                         line_number: line_number + 1,
                         value: comment,
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use crate::program::{Instruction, Program};
+
+pub fn disassemble(program: &Program) -> String {
+    let mut output = String::new();
+
+    let mut inputs: Vec<_> = program.inputs().iter().collect();
+    inputs.sort_by_key(|(register, _)| *register);
+    for (register, value) in &inputs {
+        output.push_str(&format!("// @input r{} = {}\n", register, value));
+    }
+    for test_case in program.test_cases() {
+        output.push_str(&format!("// @test {}\n", test_case));
+    }
+    if !inputs.is_empty() || !program.test_cases().is_empty() {
+        output.push('\n');
+    }
+
+    output.push_str(&disassemble_with(program, |instruction| {
+        instruction.to_string()
+    }));
+
+    output
+}
+
+pub(crate) fn disassemble_with(
+    program: &Program,
+    format_instruction: impl Fn(&Instruction) -> String,
+) -> String {
+    let mut output = String::new();
+
+    let mut labels_at: HashMap<usize, Vec<&str>> = HashMap::new();
+    for (label, &index) in program.labels() {
+        labels_at.entry(index).or_default().push(label);
+    }
+    for labels in labels_at.values_mut() {
+        labels.sort_unstable();
+    }
+
+    let width = program
+        .labels()
+        .keys()
+        .map(|label| label.len() + 2)
+        .max()
+        .unwrap_or(0);
+
+    for (index, instruction) in program.instructions().iter().enumerate() {
+        let mut label_column = String::new();
+        if let Some((last, rest)) = labels_at.get(&index).and_then(|l| l.split_last()) {
+            for label in rest {
+                output.push_str(&format!("{}:\n", label));
+            }
+            label_column = format!("{}:", last);
+        }
+        output.push_str(&format!(
+            "{:<width$}{}\n",
+            label_column,
+            format_instruction(instruction),
+        ));
+    }
+
+    // labels pointing behind the last instruction still have to be kept as jump targets
+    if let Some(labels) = labels_at.get(&program.instructions().len()) {
+        for label in labels {
+            output.push_str(&format!("{}:\n", label));
+        }
+    }
+
+    output
+}
@@ -68,6 +68,13 @@ impl std::fmt::Display for CompileError {
                     self.context, self.line_number
                 )
             }
+            CompileErrorKind::InvalidDirective => {
+                write!(
+                    f,
+                    "Invalid directive '{}' at line {}, expected e.g. '@input r1 = 5'",
+                    self.context, self.line_number
+                )
+            }
         }
     }
 }
@@ -82,25 +89,117 @@ pub enum CompileErrorKind {
     DuplicateLabelDefinition,
     MissingArgument,
     InvalidArgument,
+    InvalidDirective,
 }
 
 #[derive(Debug)]
 pub enum RuntimeError {
-    UnknownLabel { label: String },
-    MachineStopped,
+    UnknownLabel {
+        label: String,
+        line: Option<usize>,
+    },
+    MachineStopped {
+        line: Option<usize>,
+    },
+    ArithmeticOverflow {
+        line: Option<usize>,
+    },
+    UninitializedRegister {
+        register: usize,
+        line: Option<usize>,
+    },
+}
+
+impl RuntimeError {
+    pub fn line_number(&self) -> Option<usize> {
+        match self {
+            RuntimeError::UnknownLabel { line, .. } => *line,
+            RuntimeError::MachineStopped { line } => *line,
+            RuntimeError::ArithmeticOverflow { line } => *line,
+            RuntimeError::UninitializedRegister { line, .. } => *line,
+        }
+    }
+
+    pub(crate) fn at_line(mut self, line_number: Option<usize>) -> Self {
+        match &mut self {
+            RuntimeError::UnknownLabel { line, .. } => *line = line.or(line_number),
+            RuntimeError::MachineStopped { line } => *line = line.or(line_number),
+            RuntimeError::ArithmeticOverflow { line } => *line = line.or(line_number),
+            RuntimeError::UninitializedRegister { line, .. } => *line = line.or(line_number),
+        }
+        self
+    }
 }
 
 impl std::fmt::Display for RuntimeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            RuntimeError::UnknownLabel { label } => {
-                write!(f, "Tried to jump to unknown label '{}'", label)
+            RuntimeError::UnknownLabel { label, .. } => {
+                write!(f, "Tried to jump to unknown label '{}'", label)?;
             }
-            RuntimeError::MachineStopped => {
-                write!(f, "Tried to operate on a stopped machine")
+            RuntimeError::MachineStopped { .. } => {
+                write!(f, "Tried to operate on a stopped machine")?;
             }
+            RuntimeError::ArithmeticOverflow { .. } => {
+                write!(f, "Arithmetic overflow")?;
+            }
+            RuntimeError::UninitializedRegister { register, .. } => {
+                write!(f, "Tried to read uninitialised register {}", register)?;
+            }
+        }
+
+        match self.line_number() {
+            Some(line_number) => write!(f, " at line {}", line_number),
+            None => Ok(()),
         }
     }
 }
 
 impl std::error::Error for RuntimeError {}
+
+#[derive(Debug)]
+pub enum BytecodeError {
+    InvalidMagic,
+    UnsupportedVersion(u16),
+    UnexpectedEnd,
+    InvalidOpcode(u8),
+    InvalidArgument(u8),
+    InvalidJumpTarget(u64),
+    InvalidLabel,
+    InvalidSourceLines,
+    InvalidTestCase,
+    UnknownLabel(String),
+    TooLarge(&'static str),
+}
+
+impl std::fmt::Display for BytecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BytecodeError::InvalidMagic => write!(f, "Not an ARAMS bytecode file"),
+            BytecodeError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported bytecode version {}", version)
+            }
+            BytecodeError::UnexpectedEnd => write!(f, "Unexpected end of bytecode"),
+            BytecodeError::InvalidOpcode(opcode) => write!(f, "Invalid opcode {}", opcode),
+            BytecodeError::InvalidArgument(kind) => {
+                write!(f, "Invalid argument kind {} for instruction", kind)
+            }
+            BytecodeError::InvalidJumpTarget(target) => {
+                write!(f, "Jump target {} is out of bounds", target)
+            }
+            BytecodeError::InvalidLabel => write!(f, "Label is not valid UTF-8"),
+            BytecodeError::InvalidSourceLines => {
+                write!(f, "Source line table does not match the instructions")
+            }
+            BytecodeError::InvalidTestCase => write!(f, "Test case is malformed"),
+            BytecodeError::UnknownLabel(label) => {
+                write!(f, "Cannot resolve jump to unknown label '{}'", label)
+            }
+            BytecodeError::TooLarge(what) => {
+                write!(f, "The {} does not fit into the bytecode format", what)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BytecodeError {}
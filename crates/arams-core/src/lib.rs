@@ -1,11 +1,19 @@
 mod compiler;
+mod disassembler;
 mod errors;
+mod optimizer;
 mod program;
 mod runtime;
+mod testing;
 
+pub mod bytecode;
 pub mod lsp;
+pub mod remasp;
 
 pub use compiler::{IntoSourceCode, compile};
-pub use errors::{CompileError, CompileErrorKind, RuntimeError};
-pub use program::Program;
-pub use runtime::{Machine, execute};
+pub use disassembler::disassemble;
+pub use errors::{BytecodeError, CompileError, CompileErrorKind, RuntimeError};
+pub use optimizer::{Optimization, OptimizationReport, optimize};
+pub use program::{Address, Instruction, Operand, Program};
+pub use runtime::{ArithmeticMode, Machine, Snapshot, StopReason, execute, resume, step};
+pub use testing::{Mismatch, TestCase, TestOutcome, run_test};
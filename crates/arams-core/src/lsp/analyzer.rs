@@ -1,6 +1,8 @@
 use crate::{
     IntoSourceCode,
+    compiler::directives::is_invalid_directive,
     lsp::errors::LSPError,
+    lsp::position::Range,
     lsp::token::{LSPToken, LSPTokenKind},
 };
 
@@ -11,274 +13,199 @@ pub fn analyze(source_code: impl IntoSourceCode) -> Vec<Vec<LSPToken>> {
         .iter()
         .enumerate()
         .map(|(line_number, line_str)| {
-            let mut tokens: Vec<LSPToken> = vec![];
+            analyze_line(line_str, line_number, lines.len() > line_number + 1)
+        })
+        .collect()
+}
 
-            let mut words_iter = line_str.split_whitespace();
+// `newline` adds a `NewLine` token at the end, every line but the last one has it
+pub(crate) fn analyze_line(line_str: &str, line_number: usize, newline: bool) -> Vec<LSPToken> {
+    let mut tokens: Vec<LSPToken> = vec![];
+    let range = |word: &str| {
+        let start = word.as_ptr() as usize - line_str.as_ptr() as usize;
+        Range::in_line(line_str, line_number, start, word.len())
+    };
 
-            while let Some(word) = words_iter.next() {
-                match word.to_lowercase().as_str() {
-                    _ if tokens.last().is_some_and(|last_token| {
-                        matches!(
-                            last_token.kind(),
-                            LSPTokenKind::Load
-                                | LSPTokenKind::Store
-                                | LSPTokenKind::Add
-                                | LSPTokenKind::Sub
-                                | LSPTokenKind::Mul
-                                | LSPTokenKind::Div
-                                | LSPTokenKind::Goto
-                                | LSPTokenKind::Jzero
-                                | LSPTokenKind::Jnzero
-                                | LSPTokenKind::End
-                        )
-                    }) =>
-                    {
-                        // new line should have started here instead, so this should be unreachable with passing code
-                        tokens.push(LSPToken::new(
-                            LSPTokenKind::Unknown,
-                            word.to_string(),
-                            vec![LSPError::UnexpectedToken(word.to_string())],
-                        ));
-                    }
-                    "load" => match words_iter.next() {
-                        Some(arg) => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Load,
-                                word.to_string(),
-                                vec![],
-                            ));
-                            tokens.push(analyze_operand(arg));
-                        }
-                        None => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Load,
-                                word.to_string(),
-                                vec![LSPError::MissingArgument],
-                            ));
-                        }
-                    },
-                    "store" => match words_iter.next() {
-                        Some(arg) => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Store,
-                                word.to_string(),
-                                vec![],
-                            ));
-                            tokens.push(analyze_address(arg));
-                        }
-                        None => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Store,
-                                word.to_string(),
-                                vec![LSPError::MissingArgument],
-                            ));
-                        }
-                    },
-                    "add" => match words_iter.next() {
-                        Some(arg) => {
-                            tokens.push(LSPToken::new(LSPTokenKind::Add, word.to_string(), vec![]));
-                            tokens.push(analyze_operand(arg));
-                        }
-                        None => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Add,
-                                word.to_string(),
-                                vec![LSPError::MissingArgument],
-                            ));
-                        }
-                    },
-                    "sub" => match words_iter.next() {
-                        Some(arg) => {
-                            tokens.push(LSPToken::new(LSPTokenKind::Sub, word.to_string(), vec![]));
-                            tokens.push(analyze_operand(arg));
-                        }
-                        None => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Sub,
-                                word.to_string(),
-                                vec![LSPError::MissingArgument],
-                            ));
-                        }
-                    },
-                    "mul" => match words_iter.next() {
-                        Some(arg) => {
-                            tokens.push(LSPToken::new(LSPTokenKind::Mul, word.to_string(), vec![]));
-                            tokens.push(analyze_operand(arg));
-                        }
-                        None => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Mul,
-                                word.to_string(),
-                                vec![LSPError::MissingArgument],
-                            ));
-                        }
-                    },
-                    "div" => match words_iter.next() {
-                        Some(arg) => {
-                            tokens.push(LSPToken::new(LSPTokenKind::Div, word.to_string(), vec![]));
-                            tokens.push(analyze_operand(arg));
-                        }
-                        None => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Div,
-                                word.to_string(),
-                                vec![LSPError::MissingArgument],
-                            ));
-                        }
-                    },
-                    "goto" => match words_iter.next() {
-                        Some(arg) => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Goto,
-                                word.to_string(),
-                                vec![],
-                            ));
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::JumpArgument,
-                                arg.to_string(),
-                                vec![],
-                            ));
-                        }
-                        None => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Goto,
-                                word.to_string(),
-                                vec![LSPError::MissingArgument],
-                            ));
-                        }
-                    },
-                    "jzero" => match words_iter.next() {
-                        Some(arg) => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Jzero,
-                                word.to_string(),
-                                vec![],
-                            ));
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::JumpArgument,
-                                arg.to_string(),
-                                vec![],
-                            ));
-                        }
-                        None => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Jzero,
-                                word.to_string(),
-                                vec![LSPError::MissingArgument],
-                            ));
-                        }
-                    },
-                    "jnzero" => match words_iter.next() {
-                        Some(arg) => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Jnzero,
-                                word.to_string(),
-                                vec![],
-                            ));
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::JumpArgument,
-                                arg.to_string(),
-                                vec![],
-                            ));
-                        }
-                        None => {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Jnzero,
-                                word.to_string(),
-                                vec![LSPError::MissingArgument],
-                            ));
-                        }
-                    },
-                    "end" => {
-                        tokens.push(LSPToken::new(LSPTokenKind::End, word.to_string(), vec![]));
-                    }
-                    _ if word.ends_with(':') => {
-                        let label = word.trim_end_matches(':');
-                        if !label.is_empty() {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::LabelDefinition,
-                                label.to_string(),
-                                vec![],
-                            ));
-                        } else {
-                            tokens.push(LSPToken::new(
-                                LSPTokenKind::Unknown,
-                                word.to_string(),
-                                vec![LSPError::UnknownToken(word.to_string())],
-                            ));
-                        }
-                    }
-                    _ if word.starts_with("//") => {
-                        let comment = words_iter.collect::<Vec<&str>>().join(" ");
-                        tokens.push(LSPToken::new(LSPTokenKind::Comment, comment, vec![]));
-                        break; // all remaining words are part of the comment and already processed
-                    }
-                    _ => {
-                        tokens.push(LSPToken::new(
-                            LSPTokenKind::Unknown,
-                            word.to_string(),
-                            vec![LSPError::UnknownToken(word.to_string())],
-                        ));
-                    }
+    let mut words_iter = line_str.split_whitespace();
+
+    while let Some(word) = words_iter.next() {
+        if let Some(first) = word.strip_prefix("//") {
+            // the range covers the whole comment, the lexeme only the words after the `//`
+            let start = word.as_ptr() as usize - line_str.as_ptr() as usize;
+            let words: Vec<&str> = words_iter.collect();
+            // the compiler reads directives like `//@input` with the text directly after the slashes
+            let directive = std::iter::once(first)
+                .chain(words.iter().copied())
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<&str>>()
+                .join(" ");
+            let errors = if is_invalid_directive(&directive) {
+                vec![LSPError::InvalidDirective(directive)]
+            } else {
+                vec![]
+            };
+            tokens.push(LSPToken::new(
+                LSPTokenKind::Comment,
+                words.join(" "),
+                range(line_str[start..].trim_end()),
+                errors,
+            ));
+            break; // all remaining words are part of the comment and already processed
+        }
+
+        if tokens
+            .last()
+            .is_some_and(|last_token| last_token.kind().is_instruction())
+        {
+            // new line should have started here instead, so this should be unreachable with passing code
+            tokens.push(LSPToken::new(
+                LSPTokenKind::Unknown,
+                word.to_string(),
+                range(word),
+                vec![LSPError::UnexpectedToken(word.to_string())],
+            ));
+            continue;
+        }
+
+        let kind = match word.to_lowercase().as_str() {
+            "load" => LSPTokenKind::Load,
+            "store" => LSPTokenKind::Store,
+            "add" => LSPTokenKind::Add,
+            "sub" => LSPTokenKind::Sub,
+            "mul" => LSPTokenKind::Mul,
+            "div" => LSPTokenKind::Div,
+            "goto" => LSPTokenKind::Goto,
+            "jzero" => LSPTokenKind::Jzero,
+            "jnzero" => LSPTokenKind::Jnzero,
+            "end" => {
+                tokens.push(LSPToken::new(
+                    LSPTokenKind::End,
+                    word.to_string(),
+                    range(word),
+                    vec![],
+                ));
+                continue;
+            }
+            _ if word.ends_with(':') => {
+                let label = word.trim_end_matches(':');
+                if !label.is_empty() {
+                    tokens.push(LSPToken::new(
+                        LSPTokenKind::LabelDefinition,
+                        label.to_string(),
+                        range(label),
+                        vec![],
+                    ));
+                } else {
+                    tokens.push(LSPToken::new(
+                        LSPTokenKind::Unknown,
+                        word.to_string(),
+                        range(word),
+                        vec![LSPError::UnknownToken(word.to_string())],
+                    ));
                 }
+                continue;
+            }
+            _ => {
+                tokens.push(LSPToken::new(
+                    LSPTokenKind::Unknown,
+                    word.to_string(),
+                    range(word),
+                    vec![LSPError::UnknownToken(word.to_string())],
+                ));
+                continue;
             }
+        };
 
-            if lines.len() > 1 && lines.len() > line_number + 1 {
-                tokens.push(LSPToken::new(LSPTokenKind::NewLine, String::new(), vec![]));
+        match words_iter.next() {
+            Some(arg) => {
+                let argument = match kind {
+                    LSPTokenKind::Store => analyze_address(arg, range(arg)),
+                    LSPTokenKind::Goto | LSPTokenKind::Jzero | LSPTokenKind::Jnzero => {
+                        LSPToken::new(
+                            LSPTokenKind::JumpArgument,
+                            arg.to_string(),
+                            range(arg),
+                            vec![],
+                        )
+                    }
+                    _ => analyze_operand(arg, range(arg)),
+                };
+                tokens.push(LSPToken::new(kind, word.to_string(), range(word), vec![]));
+                tokens.push(argument);
+            }
+            None => {
+                tokens.push(LSPToken::new(
+                    kind,
+                    word.to_string(),
+                    range(word),
+                    vec![LSPError::MissingArgument],
+                ));
             }
+        }
+    }
 
-            tokens
-        })
-        .collect()
+    if newline {
+        let end = range(&line_str[line_str.len()..]);
+        tokens.push(LSPToken::new(
+            LSPTokenKind::NewLine,
+            String::new(),
+            end,
+            vec![],
+        ));
+    }
+
+    tokens
 }
 
-fn analyze_operand(s: &str) -> LSPToken {
+fn analyze_operand(s: &str, range: Range) -> LSPToken {
     if s.starts_with('#') {
         match s.trim_start_matches('#').parse::<u64>() {
-            Ok(_) => LSPToken::new(LSPTokenKind::ImmediateArgument, s.to_string(), vec![]),
-            Err(_) => LSPToken::new(
+            Ok(_) => LSPToken::new(
                 LSPTokenKind::ImmediateArgument,
                 s.to_string(),
-                vec![LSPError::InvalidArgument(s.to_string())],
+                range,
+                vec![],
             ),
-        }
-    } else if s.starts_with('*') {
-        match s.trim_start_matches('*').parse::<usize>() {
-            Ok(_) => LSPToken::new(LSPTokenKind::IndirectAddressArgument, s.to_string(), vec![]),
             Err(_) => LSPToken::new(
-                LSPTokenKind::IndirectAddressArgument,
+                LSPTokenKind::ImmediateArgument,
                 s.to_string(),
+                range,
                 vec![LSPError::InvalidArgument(s.to_string())],
             ),
         }
     } else {
-        match s.parse::<usize>() {
-            Ok(_) => LSPToken::new(LSPTokenKind::DirectAddressArgument, s.to_string(), vec![]),
-            Err(_) => LSPToken::new(
-                LSPTokenKind::DirectAddressArgument,
-                s.to_string(),
-                vec![LSPError::InvalidArgument(s.to_string())],
-            ),
-        }
+        analyze_address(s, range)
     }
 }
 
-fn analyze_address(s: &str) -> LSPToken {
+fn analyze_address(s: &str, range: Range) -> LSPToken {
     if s.starts_with('*') {
         match s.trim_start_matches('*').parse::<usize>() {
-            Ok(_) => LSPToken::new(LSPTokenKind::IndirectAddressArgument, s.to_string(), vec![]),
+            Ok(_) => LSPToken::new(
+                LSPTokenKind::IndirectAddressArgument,
+                s.to_string(),
+                range,
+                vec![],
+            ),
             Err(_) => LSPToken::new(
                 LSPTokenKind::IndirectAddressArgument,
                 s.to_string(),
+                range,
                 vec![LSPError::InvalidArgument(s.to_string())],
             ),
         }
     } else {
         match s.parse::<usize>() {
-            Ok(_) => LSPToken::new(LSPTokenKind::DirectAddressArgument, s.to_string(), vec![]),
+            Ok(_) => LSPToken::new(
+                LSPTokenKind::DirectAddressArgument,
+                s.to_string(),
+                range,
+                vec![],
+            ),
             Err(_) => LSPToken::new(
                 LSPTokenKind::DirectAddressArgument,
                 s.to_string(),
+                range,
                 vec![LSPError::InvalidArgument(s.to_string())],
             ),
         }
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+
+use crate::lsp::{
+    diagnostics::{Diagnostic, diagnostics_with_labels, last_token},
+    document::TextEdit,
+    errors::LSPError,
+    labels::LabelIndex,
+    position::{Position, Range},
+    token::{LSPToken, LSPTokenKind},
+};
+
+const KEYWORDS: [&str; 10] = [
+    "load", "store", "add", "sub", "mul", "div", "goto", "jzero", "jnzero", "end",
+];
+
+#[derive(Clone, Debug)]
+pub struct CodeAction {
+    title: String,
+    edit: TextEdit,
+    diagnostic: Diagnostic,
+}
+
+impl CodeAction {
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn edit(&self) -> &TextEdit {
+        &self.edit
+    }
+
+    // the diagnostic the action fixes
+    pub fn diagnostic(&self) -> &Diagnostic {
+        &self.diagnostic
+    }
+}
+
+// quick fixes for the diagnostics on the lines of `range`, a missing label is only created once
+// even if several jumps refer to it
+pub fn code_actions(lines: &[Vec<LSPToken>], range: Range) -> Vec<CodeAction> {
+    code_actions_with_labels(lines, &LabelIndex::new(lines), range)
+}
+
+pub(crate) fn code_actions_with_labels(
+    lines: &[Vec<LSPToken>],
+    labels: &LabelIndex,
+    range: Range,
+) -> Vec<CodeAction> {
+    let mut created_labels = HashSet::new();
+
+    diagnostics_with_labels(lines, labels)
+        .into_iter()
+        .filter(|diagnostic| {
+            diagnostic.range().start().line() <= range.end().line()
+                && diagnostic.range().end().line() >= range.start().line()
+        })
+        .filter(|diagnostic| match diagnostic.error() {
+            LSPError::LabelNotFound(label) => created_labels.insert(label.clone()),
+            _ => true,
+        })
+        .filter_map(|diagnostic| {
+            let (title, edit) = quick_fix(lines, &diagnostic)?;
+            Some(CodeAction {
+                title,
+                edit,
+                diagnostic,
+            })
+        })
+        .collect()
+}
+
+fn quick_fix(lines: &[Vec<LSPToken>], diagnostic: &Diagnostic) -> Option<(String, TextEdit)> {
+    let range = diagnostic.range();
+
+    match diagnostic.error() {
+        LSPError::LabelNotFound(label) => Some((
+            format!("Create label '{}'", label),
+            label_definition(lines, label),
+        )),
+        LSPError::MissingEnd => Some(("Append 'end'".to_string(), append(lines, "end"))),
+        LSPError::UnusedLabel(label) => Some((
+            format!("Remove unused label '{}'", label),
+            TextEdit::new(label_removal(lines, range), ""),
+        )),
+        LSPError::InvalidArgument(argument) => {
+            // `store` needs an address, the value of an immediate is most likely meant as one
+            let address = argument
+                .strip_prefix('#')
+                .filter(|address| address.parse::<usize>().is_ok())?;
+            let tokens = &lines[range.start().line()];
+            let index = tokens.iter().position(|token| token.range() == range)?;
+            if index == 0 || tokens[index - 1].kind() != &LSPTokenKind::Store {
+                return None;
+            }
+            Some((
+                format!("Store to register {}", address),
+                TextEdit::new(range, address),
+            ))
+        }
+        LSPError::UnknownToken(word) => {
+            let keyword = nearest_keyword(word)?;
+            Some((
+                format!("Change to '{}'", keyword),
+                TextEdit::new(range, keyword),
+            ))
+        }
+        _ => None,
+    }
+}
+
+// a new line behind the last token, trailing blank lines stay at the end
+fn append(lines: &[Vec<LSPToken>], text: &str) -> TextEdit {
+    match last_token(lines) {
+        Some(token) => {
+            let end = token.range().end();
+            TextEdit::new(Range::new(end, end), format!("\n{}", text))
+        }
+        None => TextEdit::new(Range::default(), text),
+    }
+}
+
+// a new line before the last `end`, so jumps to the label stop the program until it is moved to
+// where it belongs, programs without `end` get the label appended
+fn label_definition(lines: &[Vec<LSPToken>], label: &str) -> TextEdit {
+    let end = lines
+        .iter()
+        .flatten()
+        .rfind(|token| token.kind() == &LSPTokenKind::End);
+
+    match end {
+        Some(end) => {
+            let line = end.range().start().line();
+            // the first token of the line gives the indentation
+            let indentation = lines[line]
+                .first()
+                .map_or(0, |token| token.range().start().character());
+            let start = Position::new(line, 0);
+            TextEdit::new(
+                Range::new(start, start),
+                format!("{}{}:\n", " ".repeat(indentation), label),
+            )
+        }
+        None => append(lines, &format!("{}:", label)),
+    }
+}
+
+// the label with its `:` and the whitespace up to the next token, or the whole line if the label
+// is all there is on it
+fn label_removal(lines: &[Vec<LSPToken>], range: Range) -> Range {
+    let line = range.start().line();
+    let tokens = &lines[line];
+    let rest: Vec<&LSPToken> = tokens
+        .iter()
+        .filter(|token| token.range().start() > range.start())
+        .collect();
+    let colon = Position::new(line, range.end().character() + 1);
+
+    match rest.first() {
+        Some(next) if next.kind() != &LSPTokenKind::NewLine => {
+            Range::new(range.start(), next.range().start())
+        }
+        Some(_) => Range::new(Position::new(line, 0), Position::new(line + 1, 0)),
+        None => Range::new(range.start(), colon),
+    }
+}
+
+// the instruction closest to a misspelled word, at most a third of its letters may differ
+fn nearest_keyword(word: &str) -> Option<&'static str> {
+    let word = word.to_lowercase();
+    let limit = (word.chars().count() / 3).max(1);
+
+    KEYWORDS
+        .iter()
+        .map(|keyword| (edit_distance(&word, keyword), *keyword))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, keyword)| keyword)
+}
+
+// the optimal string alignment distance, swapping two neighbouring letters is one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            distances[i][j] = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distances[i][j] = distances[i][j].min(distances[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
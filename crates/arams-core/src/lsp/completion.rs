@@ -0,0 +1,108 @@
+use crate::lsp::{
+    labels::LabelIndex,
+    position::Position,
+    token::{LSPToken, LSPTokenKind},
+};
+
+const INSTRUCTIONS: [LSPTokenKind; 10] = [
+    LSPTokenKind::Load,
+    LSPTokenKind::Store,
+    LSPTokenKind::Add,
+    LSPTokenKind::Sub,
+    LSPTokenKind::Mul,
+    LSPTokenKind::Div,
+    LSPTokenKind::Goto,
+    LSPTokenKind::Jzero,
+    LSPTokenKind::Jnzero,
+    LSPTokenKind::End,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionKind {
+    Keyword,
+    Label,
+}
+
+#[derive(Clone, Debug)]
+pub struct Completion {
+    label: String,
+    kind: CompletionKind,
+    detail: String,
+    documentation: String,
+}
+
+impl Completion {
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn kind(&self) -> CompletionKind {
+        self.kind
+    }
+
+    pub fn detail(&self) -> &str {
+        &self.detail
+    }
+
+    pub fn documentation(&self) -> &str {
+        &self.documentation
+    }
+}
+
+// everything that fits at the cursor, filtering by the typed prefix is left to the editor:
+// instructions at the start of a line or after a label, labels after a jump
+pub fn completions(lines: &[Vec<LSPToken>], position: Position) -> Vec<Completion> {
+    completions_with_labels(lines, &LabelIndex::new(lines), position)
+}
+
+pub(crate) fn completions_with_labels(
+    lines: &[Vec<LSPToken>],
+    labels: &LabelIndex,
+    position: Position,
+) -> Vec<Completion> {
+    let tokens = lines.get(position.line()).map_or(&[][..], Vec::as_slice);
+
+    if tokens
+        .iter()
+        .any(|token| token.kind() == &LSPTokenKind::Comment && token.range().start() < position)
+    {
+        return Vec::new();
+    }
+
+    // the word being typed is not part of the context
+    let previous = tokens
+        .iter()
+        .filter(|token| token.kind() != &LSPTokenKind::NewLine)
+        .take_while(|token| token.range().end() < position)
+        .last();
+
+    match previous.map(LSPToken::kind) {
+        None | Some(LSPTokenKind::LabelDefinition) => INSTRUCTIONS
+            .iter()
+            .map(|kind| {
+                let about = kind.get_about_text();
+                let (detail, documentation) = about.split_once('\n').unwrap_or((&about, ""));
+                Completion {
+                    label: kind.to_string(),
+                    kind: CompletionKind::Keyword,
+                    detail: detail.to_string(),
+                    documentation: documentation.to_string(),
+                }
+            })
+            .collect(),
+        Some(LSPTokenKind::Goto | LSPTokenKind::Jzero | LSPTokenKind::Jnzero) => labels
+            .labels()
+            .into_iter()
+            .map(|label| Completion {
+                label: label.to_string(),
+                kind: CompletionKind::Label,
+                detail: labels
+                    .definition(label)
+                    .map(|range| format!("Label on line {}", range.start().line() + 1))
+                    .unwrap_or_default(),
+                documentation: String::new(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,107 @@
+use crate::lsp::{
+    errors::LSPError,
+    labels::LabelIndex,
+    position::Range,
+    token::{LSPToken, LSPTokenKind},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    range: Range,
+    error: LSPError,
+}
+
+impl Diagnostic {
+    pub fn new(range: Range, error: LSPError) -> Self {
+        Self { range, error }
+    }
+
+    pub fn range(&self) -> Range {
+        self.range
+    }
+
+    pub fn error(&self) -> &LSPError {
+        &self.error
+    }
+
+    // unused labels and a missing `end` do not stop the program from compiling
+    pub fn severity(&self) -> DiagnosticSeverity {
+        match self.error {
+            LSPError::UnusedLabel(_) | LSPError::MissingEnd => DiagnosticSeverity::Warning,
+            _ => DiagnosticSeverity::Error,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        self.error.to_string()
+    }
+}
+
+// the errors of the tokens and the label errors that span lines, sorted by position,
+// followed by a warning at the last token if the program has no `end`
+pub fn diagnostics(lines: &[Vec<LSPToken>]) -> Vec<Diagnostic> {
+    diagnostics_with_labels(lines, &LabelIndex::new(lines))
+}
+
+pub(crate) fn diagnostics_with_labels(
+    lines: &[Vec<LSPToken>],
+    labels: &LabelIndex,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for token in lines.iter().flatten() {
+        for error in token.errors() {
+            diagnostics.push(Diagnostic::new(token.range(), error.clone()));
+        }
+
+        match token.kind() {
+            // every definition after the first one is a duplicate
+            LSPTokenKind::LabelDefinition
+                if labels.definition(token.lexeme()) != Some(token.range()) =>
+            {
+                diagnostics.push(Diagnostic::new(
+                    token.range(),
+                    LSPError::DuplicateLabelDefinition(token.lexeme().to_string()),
+                ));
+            }
+            LSPTokenKind::LabelDefinition if labels.references(token.lexeme()).is_empty() => {
+                diagnostics.push(Diagnostic::new(
+                    token.range(),
+                    LSPError::UnusedLabel(token.lexeme().to_string()),
+                ));
+            }
+            LSPTokenKind::JumpArgument if labels.definition(token.lexeme()).is_none() => {
+                diagnostics.push(Diagnostic::new(
+                    token.range(),
+                    LSPError::LabelNotFound(token.lexeme().to_string()),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let mut tokens = lines.iter().flatten();
+    if tokens.clone().any(|token| token.kind().is_instruction())
+        && !tokens.any(|token| token.kind() == &LSPTokenKind::End)
+    {
+        let range = last_token(lines).map_or_else(Range::default, LSPToken::range);
+        diagnostics.push(Diagnostic::new(range, LSPError::MissingEnd));
+    }
+
+    diagnostics
+}
+
+// the last token that is not a newline
+pub(crate) fn last_token(lines: &[Vec<LSPToken>]) -> Option<&LSPToken> {
+    lines
+        .iter()
+        .flatten()
+        .rev()
+        .find(|token| token.kind() != &LSPTokenKind::NewLine)
+}
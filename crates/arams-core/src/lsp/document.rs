@@ -0,0 +1,184 @@
+use crate::{
+    IntoSourceCode, Machine,
+    lsp::{
+        analyzer::analyze_line,
+        code_actions::{CodeAction, code_actions_with_labels},
+        completion::{Completion, completions_with_labels},
+        diagnostics::{Diagnostic, diagnostics_with_labels},
+        hover::{Hover, hover_with_labels},
+        inlay_hints::{InlayHint, inlay_hints_with_labels},
+        labels::LabelIndex,
+        navigation::definition_with_labels,
+        position::{Position, Range, byte_offset},
+        semantic_tokens::semantic_tokens,
+        symbols::{DocumentSymbol, FoldingRange, document_symbols, folding_ranges},
+        token::LSPToken,
+    },
+};
+
+// a change of the text like an LSP `TextDocumentContentChangeEvent`
+#[derive(Clone, Debug)]
+pub struct TextEdit {
+    range: Range,
+    text: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range, text: impl Into<String>) -> Self {
+        Self {
+            range,
+            text: text.into(),
+        }
+    }
+
+    pub fn range(&self) -> Range {
+        self.range
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+// keeps the analysis of a source file up to date while it is edited, only the edited lines are
+// analysed again, the tokens and labels below them are moved if lines are inserted or removed
+#[derive(Clone, Debug)]
+pub struct Document {
+    lines: Vec<String>,
+    tokens: Vec<Vec<LSPToken>>,
+    labels: LabelIndex,
+}
+
+impl Document {
+    pub fn new(source_code: impl IntoSourceCode) -> Self {
+        let mut lines = source_code.into_lines();
+        // an empty document still has a line the cursor can be on
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        let tokens: Vec<Vec<LSPToken>> = lines
+            .iter()
+            .enumerate()
+            .map(|(line_number, line)| {
+                analyze_line(line, line_number, line_number + 1 < lines.len())
+            })
+            .collect();
+        let labels = LabelIndex::new(&tokens);
+
+        Self {
+            lines,
+            tokens,
+            labels,
+        }
+    }
+
+    pub fn edit(&mut self, edit: &TextEdit) {
+        let (start, end) = match edit.range() {
+            range if range.start() <= range.end() => (range.start(), range.end()),
+            range => (range.end(), range.start()),
+        };
+        let (start_line, start_offset) = self.offset(start);
+        let (end_line, end_offset) = self.offset(end);
+
+        let replaced = format!(
+            "{}{}{}",
+            &self.lines[start_line][..start_offset],
+            edit.text(),
+            &self.lines[end_line][end_offset..]
+        );
+        let lines: Vec<String> = replaced
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+            .collect();
+
+        for token in self.tokens[start_line..=end_line].iter().flatten() {
+            self.labels.remove(token);
+        }
+
+        let delta = lines.len() as isize - (end_line - start_line + 1) as isize;
+        if delta != 0 {
+            for token in self.tokens[end_line + 1..].iter_mut().flatten() {
+                token.shift_lines(delta);
+            }
+            self.labels.shift_lines(end_line + 1, delta);
+        }
+
+        let line_count = self.lines.len().saturating_add_signed(delta);
+        let tokens: Vec<Vec<LSPToken>> = lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let line_number = start_line + index;
+                analyze_line(line, line_number, line_number + 1 < line_count)
+            })
+            .collect();
+        for token in tokens.iter().flatten() {
+            self.labels.insert(token);
+        }
+
+        self.lines.splice(start_line..=end_line, lines);
+        self.tokens.splice(start_line..=end_line, tokens);
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn tokens(&self) -> &[Vec<LSPToken>] {
+        &self.tokens
+    }
+
+    pub fn labels(&self) -> &LabelIndex {
+        &self.labels
+    }
+
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        diagnostics_with_labels(&self.tokens, &self.labels)
+    }
+
+    pub fn completions(&self, position: Position) -> Vec<Completion> {
+        completions_with_labels(&self.tokens, &self.labels, position)
+    }
+
+    pub fn definition(&self, position: Position) -> Option<Range> {
+        definition_with_labels(&self.tokens, &self.labels, position)
+    }
+
+    // pass the machine of a running program to show the current register values
+    pub fn hover(&self, position: Position, machine: Option<&Machine>) -> Option<Hover> {
+        hover_with_labels(&self.tokens, &self.labels, position, machine)
+    }
+
+    pub fn inlay_hints(&self, machine: Option<&Machine>) -> Vec<InlayHint> {
+        inlay_hints_with_labels(&self.tokens, &self.labels, machine)
+    }
+
+    pub fn code_actions(&self, range: Range) -> Vec<CodeAction> {
+        code_actions_with_labels(&self.tokens, &self.labels, range)
+    }
+
+    pub fn semantic_tokens(&self) -> Vec<u32> {
+        semantic_tokens(&self.tokens)
+    }
+
+    pub fn document_symbols(&self) -> Vec<DocumentSymbol> {
+        document_symbols(&self.tokens)
+    }
+
+    pub fn folding_ranges(&self) -> Vec<FoldingRange> {
+        folding_ranges(&self.tokens)
+    }
+
+    // positions behind the end of the document are clamped to its end
+    fn offset(&self, position: Position) -> (usize, usize) {
+        match self.lines.get(position.line()) {
+            Some(line) => (position.line(), byte_offset(line, position.character())),
+            None => (self.lines.len() - 1, self.lines[self.lines.len() - 1].len()),
+        }
+    }
+}
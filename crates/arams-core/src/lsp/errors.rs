@@ -6,6 +6,9 @@ pub enum LSPError {
     InvalidArgument(String),
     DuplicateLabelDefinition(String),
     LabelNotFound(String),
+    UnusedLabel(String),
+    MissingEnd,
+    InvalidDirective(String),
 }
 
 impl std::fmt::Display for LSPError {
@@ -23,6 +26,13 @@ impl std::fmt::Display for LSPError {
             LSPError::LabelNotFound(label) => {
                 write!(f, "Definition for label '{}' not found", label)
             }
+            LSPError::UnusedLabel(label) => write!(f, "Label '{}' is never used", label),
+            LSPError::MissingEnd => write!(f, "The program has no 'end' instruction"),
+            LSPError::InvalidDirective(directive) => write!(
+                f,
+                "Directive '{}' is malformed, expected e.g. '@input r1 = 5' or '@test name: r1 = 5 -> acc = 5'",
+                directive
+            ),
         }
     }
 }
@@ -0,0 +1,104 @@
+use crate::IntoSourceCode;
+
+const KEYWORDS: [&str; 10] = [
+    "load", "store", "add", "sub", "mul", "div", "goto", "jzero", "jnzero", "end",
+];
+
+struct FormattedLine {
+    labels: Vec<String>,
+    code: Option<String>,
+    comment: Option<String>,
+}
+
+// formats source code the same way the disassembler prints programs, but keeps comments and blank lines
+pub fn format(source_code: impl IntoSourceCode) -> String {
+    let lines: Vec<Option<FormattedLine>> = source_code
+        .into_lines()
+        .iter()
+        .map(|line| parse_line(line))
+        .collect();
+
+    let width = lines
+        .iter()
+        .flatten()
+        .flat_map(|line| &line.labels)
+        .map(|label| label.len() + 2)
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+    let mut previous_blank = true;
+    for line in &lines {
+        let Some(line) = line else {
+            // consecutive blank lines are collapsed into one
+            if !previous_blank {
+                output.push('\n');
+            }
+            previous_blank = true;
+            continue;
+        };
+        previous_blank = false;
+
+        let mut label_column = String::new();
+        if let Some((last, rest)) = line.labels.split_last() {
+            for label in rest {
+                output.push_str(&format!("{}:\n", label));
+            }
+            label_column = format!("{}:", last);
+        }
+
+        let mut formatted = match &line.code {
+            Some(code) => format!("{:<width$}{}", label_column, code),
+            None => label_column,
+        };
+        if let Some(comment) = &line.comment {
+            if !formatted.is_empty() {
+                formatted.push(' ');
+            }
+            formatted.push_str(comment);
+        }
+
+        output.push_str(&formatted);
+        output.push('\n');
+    }
+
+    // a trailing blank line is removed
+    if previous_blank && output.ends_with("\n\n") {
+        output.pop();
+    }
+
+    output
+}
+
+fn parse_line(line: &str) -> Option<FormattedLine> {
+    let mut labels = Vec::new();
+    let mut code = Vec::new();
+    let mut comment = None;
+
+    for word in line.split_whitespace() {
+        if word.starts_with("//") {
+            // the comment is kept as written, starting at the first `//` word
+            let start = word.as_ptr() as usize - line.as_ptr() as usize;
+            comment = Some(line[start..].trim_end().to_string());
+            break;
+        }
+
+        match word.strip_suffix(':') {
+            Some(label) if code.is_empty() && !label.is_empty() => labels.push(label.to_string()),
+            _ if code.is_empty() && KEYWORDS.contains(&word.to_lowercase().as_str()) => {
+                code.push(word.to_lowercase())
+            }
+            _ => code.push(word.to_string()),
+        }
+    }
+
+    if labels.is_empty() && code.is_empty() && comment.is_none() {
+        return None;
+    }
+
+    Some(FormattedLine {
+        labels,
+        code: (!code.is_empty()).then(|| code.join(" ")),
+        comment,
+    })
+}
@@ -0,0 +1,134 @@
+use crate::{
+    Machine,
+    lsp::{
+        labels::LabelIndex,
+        navigation::token_at,
+        position::{Position, Range},
+        token::{LSPToken, LSPTokenKind},
+    },
+};
+
+#[derive(Clone, Debug)]
+pub struct Hover {
+    range: Range,
+    contents: String,
+}
+
+impl Hover {
+    pub fn range(&self) -> Range {
+        self.range
+    }
+
+    // markdown, paragraphs are separated by blank lines
+    pub fn contents(&self) -> &str {
+        &self.contents
+    }
+}
+
+// the about text of the token, for labels where they are defined and how often they are used,
+// for registers how often the program reads and writes them and, while a program is being
+// debugged, their current values
+pub fn hover(
+    lines: &[Vec<LSPToken>],
+    position: Position,
+    machine: Option<&Machine>,
+) -> Option<Hover> {
+    hover_with_labels(lines, &LabelIndex::new(lines), position, machine)
+}
+
+pub(crate) fn hover_with_labels(
+    lines: &[Vec<LSPToken>],
+    labels: &LabelIndex,
+    position: Position,
+    machine: Option<&Machine>,
+) -> Option<Hover> {
+    let token = token_at(lines, position)?;
+    let mut paragraphs = vec![token.kind().get_about_text()];
+
+    match token.kind() {
+        LSPTokenKind::LabelDefinition | LSPTokenKind::JumpArgument => {
+            let label = token.lexeme();
+            paragraphs.push(match labels.definition(label) {
+                Some(definition) => format!(
+                    "Label `{}` on line {}, {}",
+                    label,
+                    definition.start().line() + 1,
+                    count(labels.references(label).len(), "reference", "references")
+                ),
+                None => format!("Label `{}` is not defined", label),
+            });
+        }
+        LSPTokenKind::DirectAddressArgument | LSPTokenKind::IndirectAddressArgument => {
+            if let Ok(register) = token.lexeme().trim_start_matches('*').parse::<usize>() {
+                let (reads, writes) = register_usage(lines, register);
+                paragraphs.push(format!(
+                    "`r{}` is read {} and written {}",
+                    register,
+                    count(reads, "time", "times"),
+                    count(writes, "time", "times")
+                ));
+
+                if let Some(machine) = machine {
+                    let value = *machine.get_register_value(register);
+                    let address = usize::try_from(value).ok();
+                    paragraphs.push(match (token.kind(), address) {
+                        (LSPTokenKind::IndirectAddressArgument, Some(address)) => format!(
+                            "Current value: `r{} = {}`, `r{} = {}`",
+                            register,
+                            value,
+                            address,
+                            machine.get_register_value(address)
+                        ),
+                        _ => format!("Current value: `r{} = {}`", register, value),
+                    });
+                }
+            }
+        }
+        _ => {}
+    }
+
+    paragraphs.retain(|paragraph| !paragraph.is_empty());
+    if paragraphs.is_empty() {
+        return None;
+    }
+
+    Some(Hover {
+        range: token.range(),
+        contents: paragraphs.join("\n\n"),
+    })
+}
+
+// reads and writes of a register in the source code, not during an execution:
+// `*n` reads `n` to get the address, `store n` writes it, every other operand `n` reads it
+fn register_usage(lines: &[Vec<LSPToken>], register: usize) -> (usize, usize) {
+    let mut reads = 0;
+    let mut writes = 0;
+
+    for tokens in lines {
+        for pair in tokens.windows(2) {
+            let (instruction, argument) = (&pair[0], &pair[1]);
+            let indirect = match argument.kind() {
+                LSPTokenKind::DirectAddressArgument => false,
+                LSPTokenKind::IndirectAddressArgument => true,
+                _ => continue,
+            };
+            if argument.lexeme().trim_start_matches('*').parse() != Ok(register) {
+                continue;
+            }
+
+            match instruction.kind() {
+                LSPTokenKind::Store if !indirect => writes += 1,
+                _ => reads += 1,
+            }
+        }
+    }
+
+    (reads, writes)
+}
+
+fn count(count: usize, singular: &str, plural: &str) -> String {
+    match count {
+        1 => format!("1 {}", singular),
+        count => format!("{} {}", count, plural),
+    }
+}
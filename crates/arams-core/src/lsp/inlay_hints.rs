@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use crate::{
+    Machine,
+    lsp::{
+        labels::LabelIndex,
+        position::Position,
+        token::{LSPToken, LSPTokenKind},
+    },
+};
+
+#[derive(Clone, Debug)]
+pub struct InlayHint {
+    position: Position,
+    label: String,
+    tooltip: String,
+}
+
+impl InlayHint {
+    // directly behind the argument the hint is about
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn tooltip(&self) -> &str {
+        &self.tooltip
+    }
+}
+
+// where jumps go to and how indirect operands are resolved, with the resolved addresses while a
+// program is being debugged
+pub fn inlay_hints(lines: &[Vec<LSPToken>], machine: Option<&Machine>) -> Vec<InlayHint> {
+    inlay_hints_with_labels(lines, &LabelIndex::new(lines), machine)
+}
+
+pub(crate) fn inlay_hints_with_labels(
+    lines: &[Vec<LSPToken>],
+    labels: &LabelIndex,
+    machine: Option<&Machine>,
+) -> Vec<InlayHint> {
+    // a label refers to the instruction following it
+    let mut targets: HashMap<&str, usize> = HashMap::new();
+    let mut instructions = 0;
+    for token in lines.iter().flatten() {
+        match token.kind() {
+            kind if kind.is_instruction() => instructions += 1,
+            LSPTokenKind::LabelDefinition
+                if labels.definition(token.lexeme()) == Some(token.range()) =>
+            {
+                targets.insert(token.lexeme(), instructions);
+            }
+            _ => {}
+        }
+    }
+
+    let mut hints = Vec::new();
+    for token in lines.iter().flatten() {
+        if !token.errors().is_empty() {
+            continue;
+        }
+
+        match token.kind() {
+            LSPTokenKind::JumpArgument => {
+                let (Some(definition), Some(instruction)) = (
+                    labels.definition(token.lexeme()),
+                    targets.get(token.lexeme()),
+                ) else {
+                    continue;
+                };
+                let line = definition.start().line() + 1;
+                hints.push(InlayHint {
+                    position: token.range().end(),
+                    label: format!("line {}", line),
+                    tooltip: format!("Jumps to instruction {} on line {}", instruction, line),
+                });
+            }
+            LSPTokenKind::IndirectAddressArgument => {
+                let Ok(register) = token.lexeme().trim_start_matches('*').parse::<usize>() else {
+                    continue;
+                };
+                let explanation = format!("value of r[{}] used as address", register);
+                let label = match machine {
+                    Some(machine) => {
+                        let address = machine.get_register_value(register);
+                        format!("r[{}] = {} → r[{}]", register, address, address)
+                    }
+                    None => explanation.clone(),
+                };
+                hints.push(InlayHint {
+                    position: token.range().end(),
+                    label,
+                    tooltip: explanation,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    hints
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use crate::lsp::{
+    position::Range,
+    token::{LSPToken, LSPTokenKind},
+};
+
+// where labels are defined and where jumps refer to them, in source order
+#[derive(Clone, Debug, Default)]
+pub struct LabelIndex {
+    definitions: HashMap<String, Vec<Range>>,
+    references: HashMap<String, Vec<Range>>,
+}
+
+impl LabelIndex {
+    pub fn new(lines: &[Vec<LSPToken>]) -> Self {
+        let mut index = Self::default();
+        for token in lines.iter().flatten() {
+            index.insert(token);
+        }
+        index
+    }
+
+    // the ranges of a label stay sorted, so the first definition is always the earliest one
+    pub(crate) fn insert(&mut self, token: &LSPToken) {
+        let Some(entries) = self.entries(token.kind()) else {
+            return;
+        };
+        let ranges = entries.entry(token.lexeme().to_string()).or_default();
+        let index = ranges.partition_point(|range| *range < token.range());
+        ranges.insert(index, token.range());
+    }
+
+    pub(crate) fn remove(&mut self, token: &LSPToken) {
+        let Some(entries) = self.entries(token.kind()) else {
+            return;
+        };
+        if let Some(ranges) = entries.get_mut(token.lexeme()) {
+            ranges.retain(|range| *range != token.range());
+            if ranges.is_empty() {
+                entries.remove(token.lexeme());
+            }
+        }
+    }
+
+    // moves every range starting at or after `line` by `delta` lines
+    pub(crate) fn shift_lines(&mut self, line: usize, delta: isize) {
+        for range in self
+            .definitions
+            .values_mut()
+            .chain(self.references.values_mut())
+            .flatten()
+            .filter(|range| range.start().line() >= line)
+        {
+            range.shift_lines(delta);
+        }
+    }
+
+    fn entries(&mut self, kind: &LSPTokenKind) -> Option<&mut HashMap<String, Vec<Range>>> {
+        match kind {
+            LSPTokenKind::LabelDefinition => Some(&mut self.definitions),
+            LSPTokenKind::JumpArgument => Some(&mut self.references),
+            _ => None,
+        }
+    }
+
+    // the first definition is the one jumps go to, later ones are duplicates
+    pub fn definition(&self, label: &str) -> Option<Range> {
+        self.definitions
+            .get(label)
+            .and_then(|ranges| ranges.first())
+            .copied()
+    }
+
+    pub fn definitions(&self, label: &str) -> &[Range] {
+        self.definitions.get(label).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn references(&self, label: &str) -> &[Range] {
+        self.references.get(label).map_or(&[], Vec::as_slice)
+    }
+
+    // all defined labels, sorted by name
+    pub fn labels(&self) -> Vec<&str> {
+        let mut labels: Vec<&str> = self.definitions.keys().map(String::as_str).collect();
+        labels.sort_unstable();
+        labels
+    }
+}
@@ -1,7 +1,33 @@
 mod analyzer;
+mod code_actions;
+mod completion;
+mod diagnostics;
+mod document;
 mod errors;
+mod formatter;
+mod hover;
+mod inlay_hints;
+mod labels;
+mod navigation;
+mod position;
+mod semantic_tokens;
+mod symbols;
 mod token;
 
 pub use analyzer::analyze;
+pub use code_actions::{CodeAction, code_actions};
+pub use completion::{Completion, CompletionKind, completions};
+pub use diagnostics::{Diagnostic, DiagnosticSeverity, diagnostics};
+pub use document::{Document, TextEdit};
 pub use errors::LSPError;
+pub use formatter::format;
+pub use hover::{Hover, hover};
+pub use inlay_hints::{InlayHint, inlay_hints};
+pub use labels::LabelIndex;
+pub use navigation::{definition, token_at};
+pub use position::{Position, Range};
+pub use semantic_tokens::{SEMANTIC_TOKEN_MODIFIERS, SEMANTIC_TOKEN_TYPES, semantic_tokens};
+pub use symbols::{
+    DocumentSymbol, FoldingRange, FoldingRangeKind, document_symbols, folding_ranges,
+};
 pub use token::{LSPToken, LSPTokenKind};
@@ -0,0 +1,32 @@
+use crate::lsp::{
+    labels::LabelIndex,
+    position::{Position, Range},
+    token::{LSPToken, LSPTokenKind},
+};
+
+// the token under the cursor, a cursor directly behind a token is still on it
+pub fn token_at(lines: &[Vec<LSPToken>], position: Position) -> Option<&LSPToken> {
+    lines
+        .get(position.line())?
+        .iter()
+        .find(|token| token.kind() != &LSPTokenKind::NewLine && token.range().contains(position))
+}
+
+// the definition of the label under the cursor, for jump arguments and label definitions
+pub fn definition(lines: &[Vec<LSPToken>], position: Position) -> Option<Range> {
+    definition_with_labels(lines, &LabelIndex::new(lines), position)
+}
+
+pub(crate) fn definition_with_labels(
+    lines: &[Vec<LSPToken>],
+    labels: &LabelIndex,
+    position: Position,
+) -> Option<Range> {
+    let token = token_at(lines, position)?;
+    match token.kind() {
+        LSPTokenKind::JumpArgument | LSPTokenKind::LabelDefinition => {
+            labels.definition(token.lexeme())
+        }
+        _ => None,
+    }
+}
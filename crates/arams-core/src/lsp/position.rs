@@ -0,0 +1,79 @@
+// zero-based like in LSP, characters are counted in UTF-16 code units as in JavaScript strings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    line: usize,
+    character: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, character: usize) -> Self {
+        Self { line, character }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn character(&self) -> usize {
+        self.character
+    }
+
+    // moves the position by `delta` lines, used when lines are inserted or removed above it
+    pub(crate) fn shift_lines(&mut self, delta: isize) {
+        self.line = self.line.saturating_add_signed(delta);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Range {
+    start: Position,
+    end: Position,
+}
+
+impl Range {
+    pub fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+
+    // the range of `len` bytes starting at byte `start` of a line
+    pub(crate) fn in_line(line: &str, line_number: usize, start: usize, len: usize) -> Self {
+        Self {
+            start: Position::new(line_number, utf16_len(&line[..start])),
+            end: Position::new(line_number, utf16_len(&line[..start + len])),
+        }
+    }
+
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    pub(crate) fn shift_lines(&mut self, delta: isize) {
+        self.start.shift_lines(delta);
+        self.end.shift_lines(delta);
+    }
+
+    // the end is included, a cursor directly behind a word is still on it
+    pub fn contains(&self, position: Position) -> bool {
+        self.start <= position && position <= self.end
+    }
+}
+
+pub(crate) fn utf16_len(s: &str) -> usize {
+    s.encode_utf16().count()
+}
+
+// the byte offset of a character position, positions behind the end of the line are clamped
+pub(crate) fn byte_offset(line: &str, character: usize) -> usize {
+    let mut utf16 = 0;
+    for (offset, c) in line.char_indices() {
+        if utf16 >= character {
+            return offset;
+        }
+        utf16 += c.len_utf16();
+    }
+    line.len()
+}
@@ -0,0 +1,56 @@
+use crate::lsp::token::{LSPToken, LSPTokenKind};
+
+// the legend of the encoded token types and modifiers, indices refer to these
+pub const SEMANTIC_TOKEN_TYPES: [&str; 5] =
+    ["keyword", "function", "number", "variable", "comment"];
+pub const SEMANTIC_TOKEN_MODIFIERS: [&str; 1] = ["declaration"];
+
+const KEYWORD: u32 = 0;
+const FUNCTION: u32 = 1;
+const NUMBER: u32 = 2;
+const VARIABLE: u32 = 3;
+const COMMENT: u32 = 4;
+const DECLARATION: u32 = 1 << 0;
+
+// encoded like LSP semantic tokens: five integers per token, the line and start relative to the
+// previous token, the length, the type and the modifier bits
+pub fn semantic_tokens(lines: &[Vec<LSPToken>]) -> Vec<u32> {
+    let mut data = Vec::new();
+    let mut previous_line = 0;
+    let mut previous_start = 0;
+
+    for token in lines.iter().flatten() {
+        let (token_type, modifiers) = match token.kind() {
+            kind if kind.is_instruction() => (KEYWORD, 0),
+            LSPTokenKind::LabelDefinition => (FUNCTION, DECLARATION),
+            LSPTokenKind::JumpArgument => (FUNCTION, 0),
+            LSPTokenKind::ImmediateArgument => (NUMBER, 0),
+            LSPTokenKind::DirectAddressArgument | LSPTokenKind::IndirectAddressArgument => {
+                (VARIABLE, 0)
+            }
+            LSPTokenKind::Comment => (COMMENT, 0),
+            _ => continue,
+        };
+
+        let start = token.range().start();
+        let line = start.line();
+        let character = start.character();
+        let delta_start = if line == previous_line {
+            character - previous_start
+        } else {
+            character
+        };
+
+        data.extend([
+            (line - previous_line) as u32,
+            delta_start as u32,
+            (token.range().end().character() - character) as u32,
+            token_type,
+            modifiers,
+        ]);
+        previous_line = line;
+        previous_start = character;
+    }
+
+    data
+}
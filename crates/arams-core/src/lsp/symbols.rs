@@ -0,0 +1,157 @@
+use crate::lsp::{
+    position::Range,
+    token::{LSPToken, LSPTokenKind},
+};
+
+#[derive(Clone, Debug)]
+pub struct DocumentSymbol {
+    name: String,
+    detail: String,
+    range: Range,
+    selection_range: Range,
+}
+
+impl DocumentSymbol {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // the number of instructions in the section
+    pub fn detail(&self) -> &str {
+        &self.detail
+    }
+
+    // the label and its instructions up to the next label
+    pub fn range(&self) -> Range {
+        self.range
+    }
+
+    // the name of the label
+    pub fn selection_range(&self) -> Range {
+        self.selection_range
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FoldingRangeKind {
+    Region,
+    Comment,
+}
+
+// whole lines, the end line is included
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FoldingRange {
+    start_line: usize,
+    end_line: usize,
+    kind: FoldingRangeKind,
+}
+
+impl FoldingRange {
+    pub fn start_line(&self) -> usize {
+        self.start_line
+    }
+
+    pub fn end_line(&self) -> usize {
+        self.end_line
+    }
+
+    pub fn kind(&self) -> FoldingRangeKind {
+        self.kind
+    }
+}
+
+// one symbol per label definition, a section ends at the last instruction before the next label
+pub fn document_symbols(lines: &[Vec<LSPToken>]) -> Vec<DocumentSymbol> {
+    let tokens: Vec<&LSPToken> = lines
+        .iter()
+        .flatten()
+        .filter(|token| token.kind() != &LSPTokenKind::NewLine)
+        .collect();
+    let definitions: Vec<usize> = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| token.kind() == &LSPTokenKind::LabelDefinition)
+        .map(|(index, _)| index)
+        .collect();
+
+    definitions
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = definitions.get(i + 1).copied().unwrap_or(tokens.len());
+            let section = &tokens[start..end];
+            let instructions = section
+                .iter()
+                .filter(|token| token.kind().is_instruction())
+                .count();
+            // comment lines in front of the next label belong to it, a comment behind the
+            // last instruction still belongs to the section
+            let last_code = section
+                .iter()
+                .rfind(|token| token.kind() != &LSPTokenKind::Comment)
+                .unwrap_or(&tokens[start]);
+            let last = section
+                .iter()
+                .rfind(|token| token.range().start().line() == last_code.range().start().line())
+                .unwrap_or(last_code);
+
+            DocumentSymbol {
+                name: tokens[start].lexeme().to_string(),
+                detail: match instructions {
+                    1 => "1 instruction".to_string(),
+                    count => format!("{} instructions", count),
+                },
+                range: Range::new(tokens[start].range().start(), last.range().end()),
+                selection_range: tokens[start].range(),
+            }
+        })
+        .collect()
+}
+
+// label sections and runs of lines that only contain a comment, sorted by their start line
+pub fn folding_ranges(lines: &[Vec<LSPToken>]) -> Vec<FoldingRange> {
+    let mut ranges: Vec<FoldingRange> = document_symbols(lines)
+        .iter()
+        .map(|symbol| FoldingRange {
+            start_line: symbol.range().start().line(),
+            end_line: symbol.range().end().line(),
+            kind: FoldingRangeKind::Region,
+        })
+        .collect();
+
+    let mut comment_start = None;
+    for (line, tokens) in lines.iter().enumerate() {
+        let comment_only = tokens
+            .iter()
+            .filter(|token| token.kind() != &LSPTokenKind::NewLine)
+            .all(|token| token.kind() == &LSPTokenKind::Comment)
+            && tokens
+                .iter()
+                .any(|token| token.kind() == &LSPTokenKind::Comment);
+
+        match (comment_only, comment_start) {
+            (true, None) => comment_start = Some(line),
+            (false, Some(start)) => {
+                ranges.push(comment_range(start, line - 1));
+                comment_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = comment_start {
+        ranges.push(comment_range(start, lines.len() - 1));
+    }
+
+    // a single line cannot be folded
+    ranges.retain(|range| range.end_line > range.start_line);
+    ranges.sort_by_key(|range| (range.start_line, range.end_line));
+    ranges
+}
+
+fn comment_range(start_line: usize, end_line: usize) -> FoldingRange {
+    FoldingRange {
+        start_line,
+        end_line,
+        kind: FoldingRangeKind::Comment,
+    }
+}
@@ -1,17 +1,19 @@
-use crate::lsp::errors::LSPError;
+use crate::lsp::{errors::LSPError, position::Range};
 
 #[derive(Clone, Debug)]
 pub struct LSPToken {
     kind: LSPTokenKind,
     lexeme: String,
+    range: Range,
     errors: Vec<LSPError>,
 }
 
 impl LSPToken {
-    pub fn new(kind: LSPTokenKind, lexeme: String, errors: Vec<LSPError>) -> Self {
+    pub fn new(kind: LSPTokenKind, lexeme: String, range: Range, errors: Vec<LSPError>) -> Self {
         Self {
             kind,
             lexeme,
+            range,
             errors,
         }
     }
@@ -24,9 +26,18 @@ impl LSPToken {
         &self.lexeme
     }
 
+    // label definitions do not include the `:`, comments include the `//`
+    pub fn range(&self) -> Range {
+        self.range
+    }
+
     pub fn errors(&self) -> &Vec<LSPError> {
         &self.errors
     }
+
+    pub(crate) fn shift_lines(&mut self, delta: isize) {
+        self.range.shift_lines(delta);
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -77,6 +88,22 @@ impl std::fmt::Display for LSPTokenKind {
 }
 
 impl LSPTokenKind {
+    pub fn is_instruction(&self) -> bool {
+        matches!(
+            self,
+            LSPTokenKind::Load
+                | LSPTokenKind::Store
+                | LSPTokenKind::Add
+                | LSPTokenKind::Sub
+                | LSPTokenKind::Mul
+                | LSPTokenKind::Div
+                | LSPTokenKind::Goto
+                | LSPTokenKind::Jzero
+                | LSPTokenKind::Jnzero
+                | LSPTokenKind::End
+        )
+    }
+
     pub fn get_about_text(&self) -> String {
         match &self {
             LSPTokenKind::Load => "Syntax: `load <operand>`\nLoads the value of the operand into the accumulator.".to_string(),
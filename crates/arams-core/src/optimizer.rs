@@ -0,0 +1,323 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::program::{Address, Instruction, Operand, Program};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Optimization {
+    RedundantLoadRemoved { address: usize },
+    NoOpRemoved { instruction: Instruction },
+    JumpThreaded { from: String, to: String },
+    UnreachableRemoved { instruction: Instruction },
+    UnusedLabelRemoved { label: String },
+}
+
+impl std::fmt::Display for Optimization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Optimization::RedundantLoadRemoved { address } => write!(
+                f,
+                "removed `load {}`, the accumulator already holds the value stored to register {}",
+                address, address
+            ),
+            Optimization::NoOpRemoved { instruction } => {
+                write!(
+                    f,
+                    "removed `{}`, it does not change the accumulator",
+                    instruction
+                )
+            }
+            Optimization::JumpThreaded { from, to } => write!(
+                f,
+                "jump to '{}' now directly targets '{}' instead of passing through a `goto`",
+                from, to
+            ),
+            Optimization::UnreachableRemoved { instruction } => {
+                write!(f, "removed `{}`, it can never be executed", instruction)
+            }
+            Optimization::UnusedLabelRemoved { label } => {
+                write!(f, "removed label '{}', it is no longer jumped to", label)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OptimizationReport {
+    instructions_before: usize,
+    instructions_after: usize,
+    changes: Vec<(Option<usize>, Optimization)>,
+}
+
+impl OptimizationReport {
+    pub fn instructions_before(&self) -> usize {
+        self.instructions_before
+    }
+
+    pub fn instructions_after(&self) -> usize {
+        self.instructions_after
+    }
+
+    // every change together with the source line of the affected instruction
+    pub fn changes(&self) -> &[(Option<usize>, Optimization)] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl std::fmt::Display for OptimizationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} -> {} instructions, {} changes",
+            self.instructions_before,
+            self.instructions_after,
+            self.changes.len()
+        )?;
+        for (line, change) in &self.changes {
+            match line {
+                Some(line) => writeln!(f, "  line {}: {}", line, change)?,
+                None => writeln!(f, "  {}", change)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn optimize(program: &Program) -> (Program, OptimizationReport) {
+    let mut optimizer = Optimizer {
+        instructions: program.instructions().to_vec(),
+        source_lines: (0..program.instructions().len())
+            .map(|index| program.source_line(index))
+            .collect(),
+        labels: program.labels().clone(),
+        changes: Vec::new(),
+    };
+
+    // every pass can enable further optimizations of the other passes
+    loop {
+        let changes = optimizer.changes.len();
+
+        optimizer.thread_jumps();
+        optimizer.remove_redundant_loads();
+        optimizer.remove_no_ops();
+        optimizer.remove_unreachable();
+
+        if optimizer.changes.len() == changes {
+            break;
+        }
+    }
+    optimizer.remove_unused_labels();
+
+    let report = OptimizationReport {
+        instructions_before: program.instructions().len(),
+        instructions_after: optimizer.instructions.len(),
+        changes: optimizer.changes,
+    };
+
+    let source_lines = if program.source_lines().is_empty() {
+        Vec::new()
+    } else {
+        optimizer.source_lines.into_iter().flatten().collect()
+    };
+
+    (
+        Program::new(optimizer.instructions, optimizer.labels, source_lines)
+            .with_inputs(program.inputs().clone())
+            .with_test_cases(program.test_cases().to_vec()),
+        report,
+    )
+}
+
+struct Optimizer {
+    instructions: Vec<Instruction>,
+    source_lines: Vec<Option<usize>>,
+    labels: HashMap<String, usize>,
+    changes: Vec<(Option<usize>, Optimization)>,
+}
+
+impl Optimizer {
+    fn thread_jumps(&mut self) {
+        for index in 0..self.instructions.len() {
+            let label = match &self.instructions[index] {
+                Instruction::Goto(label)
+                | Instruction::Jzero(label)
+                | Instruction::Jnzero(label) => label.clone(),
+                _ => continue,
+            };
+
+            // follow chains of gotos, a cycle of gotos is an infinite loop and stays as it is
+            let mut target = label.clone();
+            let mut visited = HashSet::from([label.clone()]);
+            while let Some(Instruction::Goto(next)) = self
+                .labels
+                .get(&target)
+                .and_then(|&target_index| self.instructions.get(target_index))
+            {
+                if !visited.insert(next.clone()) {
+                    target = label.clone();
+                    break;
+                }
+                target = next.clone();
+            }
+
+            if target != label {
+                match &mut self.instructions[index] {
+                    Instruction::Goto(label)
+                    | Instruction::Jzero(label)
+                    | Instruction::Jnzero(label) => *label = target.clone(),
+                    _ => unreachable!(),
+                }
+                self.changes.push((
+                    self.source_lines[index],
+                    Optimization::JumpThreaded {
+                        from: label,
+                        to: target,
+                    },
+                ));
+            }
+        }
+    }
+
+    fn remove_redundant_loads(&mut self) {
+        let jump_targets: HashSet<usize> = self.labels.values().copied().collect();
+
+        let mut remove = vec![false; self.instructions.len()];
+        for (index, remove) in remove.iter_mut().enumerate().skip(1) {
+            if let (
+                Instruction::Store(Address::Direct(stored)),
+                Instruction::Load(Operand::DirectAddress(loaded)),
+            ) = (&self.instructions[index - 1], &self.instructions[index])
+                && stored == loaded
+                && !jump_targets.contains(&index)
+            {
+                *remove = true;
+                self.changes.push((
+                    self.source_lines[index],
+                    Optimization::RedundantLoadRemoved { address: *loaded },
+                ));
+            }
+        }
+
+        self.remove(&remove);
+    }
+
+    fn remove_no_ops(&mut self) {
+        let remove: Vec<bool> = self
+            .instructions
+            .iter()
+            .map(|instruction| {
+                matches!(
+                    instruction,
+                    Instruction::Add(Operand::Immediate(0))
+                        | Instruction::Sub(Operand::Immediate(0))
+                        | Instruction::Mul(Operand::Immediate(1))
+                        | Instruction::Div(Operand::Immediate(1))
+                )
+            })
+            .collect();
+
+        for (index, _) in remove.iter().enumerate().filter(|(_, remove)| **remove) {
+            self.changes.push((
+                self.source_lines[index],
+                Optimization::NoOpRemoved {
+                    instruction: self.instructions[index].clone(),
+                },
+            ));
+        }
+
+        self.remove(&remove);
+    }
+
+    fn remove_unreachable(&mut self) {
+        let mut reachable = vec![false; self.instructions.len()];
+        let mut pending = vec![0];
+
+        while let Some(index) = pending.pop() {
+            if index >= self.instructions.len() || reachable[index] {
+                continue;
+            }
+            reachable[index] = true;
+
+            match &self.instructions[index] {
+                Instruction::Goto(label) => pending.extend(self.labels.get(label)),
+                Instruction::Jzero(label) | Instruction::Jnzero(label) => {
+                    pending.extend(self.labels.get(label));
+                    pending.push(index + 1);
+                }
+                Instruction::End => {}
+                _ => pending.push(index + 1),
+            }
+        }
+
+        let remove: Vec<bool> = reachable.iter().map(|reachable| !reachable).collect();
+
+        for (index, _) in remove.iter().enumerate().filter(|(_, remove)| **remove) {
+            self.changes.push((
+                self.source_lines[index],
+                Optimization::UnreachableRemoved {
+                    instruction: self.instructions[index].clone(),
+                },
+            ));
+        }
+
+        self.remove(&remove);
+    }
+
+    fn remove_unused_labels(&mut self) {
+        let used: HashSet<&String> = self
+            .instructions
+            .iter()
+            .filter_map(|instruction| match instruction {
+                Instruction::Goto(label)
+                | Instruction::Jzero(label)
+                | Instruction::Jnzero(label) => Some(label),
+                _ => None,
+            })
+            .collect();
+
+        let mut unused: Vec<String> = self
+            .labels
+            .keys()
+            .filter(|label| !used.contains(label))
+            .cloned()
+            .collect();
+        unused.sort_by_key(|label| (self.labels[label], label.clone()));
+
+        for label in unused {
+            let index = self.labels.remove(&label).unwrap_or_default();
+            self.changes.push((
+                self.source_lines.get(index).copied().flatten(),
+                Optimization::UnusedLabelRemoved { label },
+            ));
+        }
+    }
+
+    // removes the marked instructions, labels of removed instructions move to the next remaining instruction
+    fn remove(&mut self, remove: &[bool]) {
+        if !remove.contains(&true) {
+            return;
+        }
+
+        let mut new_indices = Vec::with_capacity(remove.len() + 1);
+        let mut kept = 0;
+        for &remove in remove {
+            new_indices.push(kept);
+            if !remove {
+                kept += 1;
+            }
+        }
+        new_indices.push(kept);
+
+        for index in self.labels.values_mut() {
+            *index = new_indices[*index];
+        }
+
+        let mut remove_iter = remove.iter();
+        self.instructions.retain(|_| !remove_iter.next().unwrap());
+        let mut remove_iter = remove.iter();
+        self.source_lines.retain(|_| !remove_iter.next().unwrap());
+    }
+}
@@ -1,9 +1,12 @@
-use crate::errors::RuntimeError;
+use crate::{errors::RuntimeError, testing::TestCase};
 use std::collections::HashMap;
 
 pub struct ProgramBuilder {
     instructions: Vec<Instruction>,
     labels: HashMap<String, usize>,
+    source_lines: Vec<usize>,
+    inputs: HashMap<usize, u64>,
+    test_cases: Vec<TestCase>,
 }
 
 impl ProgramBuilder {
@@ -11,19 +14,33 @@ impl ProgramBuilder {
         Self {
             instructions: Vec::new(),
             labels: HashMap::new(),
+            source_lines: Vec::new(),
+            inputs: HashMap::new(),
+            test_cases: Vec::new(),
         }
     }
 
-    pub fn add_instruction(&mut self, instruction: Instruction) {
+    pub fn add_instruction(&mut self, instruction: Instruction, line_number: usize) {
         self.instructions.push(instruction);
+        self.source_lines.push(line_number);
     }
 
     pub fn add_label(&mut self, label: String) {
         self.labels.insert(label, self.instructions.len());
     }
 
+    pub fn add_input(&mut self, register: usize, value: u64) {
+        self.inputs.insert(register, value);
+    }
+
+    pub fn add_test_case(&mut self, test_case: TestCase) {
+        self.test_cases.push(test_case);
+    }
+
     pub fn build(self) -> Program {
-        Program::new(self.instructions, self.labels)
+        Program::new(self.instructions, self.labels, self.source_lines)
+            .with_inputs(self.inputs)
+            .with_test_cases(self.test_cases)
     }
 
     pub fn label_exists(&self, label: &String) -> bool {
@@ -32,43 +49,128 @@ impl ProgramBuilder {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Program {
     instructions: Vec<Instruction>,
     labels: HashMap<String, usize>,
-    program_counter: usize,
+    // source line of each instruction, empty if the program was loaded without debug information
+    #[cfg_attr(feature = "serde", serde(default))]
+    source_lines: Vec<usize>,
+    // default register values declared with `// @input` directives
+    #[cfg_attr(feature = "serde", serde(default))]
+    inputs: HashMap<usize, u64>,
+    // test cases declared with `// @test` directives, they do not change the behaviour of the program
+    #[cfg_attr(feature = "serde", serde(default))]
+    test_cases: Vec<TestCase>,
 }
 
 impl Program {
-    pub(crate) fn new(instructions: Vec<Instruction>, labels: HashMap<String, usize>) -> Self {
+    pub(crate) fn new(
+        instructions: Vec<Instruction>,
+        labels: HashMap<String, usize>,
+        source_lines: Vec<usize>,
+    ) -> Self {
         Self {
             instructions,
             labels,
-            program_counter: 0,
+            source_lines,
+            inputs: HashMap::new(),
+            test_cases: Vec::new(),
         }
     }
 
-    pub fn fetch(&mut self) -> Option<Instruction> {
-        let instruction = self.instructions.get(self.program_counter)?.clone();
-        Some(instruction)
+    pub(crate) fn with_inputs(mut self, inputs: HashMap<usize, u64>) -> Self {
+        self.inputs = inputs;
+        self
+    }
+
+    pub(crate) fn with_test_cases(mut self, test_cases: Vec<TestCase>) -> Self {
+        self.test_cases = test_cases;
+        self
+    }
+
+    // e.g. graded submissions must not preseed registers or bring their own test cases
+    pub fn without_directives(mut self) -> Self {
+        self.inputs.clear();
+        self.test_cases.clear();
+        self
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    pub fn labels(&self) -> &HashMap<String, usize> {
+        &self.labels
+    }
+
+    pub fn inputs(&self) -> &HashMap<usize, u64> {
+        &self.inputs
     }
 
-    pub fn advance(&mut self) {
-        self.program_counter += 1;
+    pub fn test_cases(&self) -> &[TestCase] {
+        &self.test_cases
     }
 
-    pub fn goto(&mut self, label: &String) -> Result<(), RuntimeError> {
-        if let Some(&line_number) = self.labels.get(label) {
-            self.program_counter = line_number;
-            Ok(())
-        } else {
-            Err(RuntimeError::UnknownLabel {
+    // the declared inputs are defaults, the given registers override them
+    pub fn initial_registers(&self, registers: &HashMap<usize, u64>) -> HashMap<usize, u64> {
+        let mut initial_registers = self.inputs.clone();
+        initial_registers.extend(registers);
+        initial_registers
+    }
+
+    pub fn source_lines(&self) -> &[usize] {
+        &self.source_lines
+    }
+
+    pub fn source_line(&self, index: usize) -> Option<usize> {
+        self.source_lines.get(index).copied()
+    }
+
+    // the label of the section an instruction belongs to, i.e. the closest label defined at or before it
+    pub fn label_at(&self, index: usize) -> Option<&str> {
+        self.labels
+            .iter()
+            .filter(|&(_, &label_index)| label_index <= index)
+            .max_by(|(a_label, a_index), (b_label, b_index)| {
+                a_index.cmp(b_index).then(b_label.cmp(a_label))
+            })
+            .map(|(label, _)| label.as_str())
+    }
+
+    pub fn instruction_at_line(&self, line_number: usize) -> Option<usize> {
+        self.source_lines
+            .iter()
+            .position(|&source_line| source_line == line_number)
+    }
+
+    pub fn fetch(&self, program_counter: usize) -> Option<&Instruction> {
+        self.instructions.get(program_counter)
+    }
+
+    pub fn resolve_label(&self, label: &str) -> Result<usize, RuntimeError> {
+        self.labels
+            .get(label)
+            .copied()
+            .ok_or_else(|| RuntimeError::UnknownLabel {
                 label: label.to_string(),
+                line: None,
             })
-        }
+    }
+}
+
+// source lines are debug information only, two programs are equal if they behave the same
+impl PartialEq for Program {
+    fn eq(&self, other: &Self) -> bool {
+        self.instructions == other.instructions
+            && self.labels == other.labels
+            && self.inputs == other.inputs
+            && self.test_cases == other.test_cases
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Instruction {
     Load(Operand),
     Store(Address),
@@ -83,6 +185,7 @@ pub enum Instruction {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operand {
     Immediate(u64),
     DirectAddress(usize),
@@ -90,7 +193,44 @@ pub enum Operand {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Address {
     Direct(usize),
     Indirect(usize),
 }
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Load(operand) => write!(f, "load {}", operand),
+            Instruction::Store(address) => write!(f, "store {}", address),
+            Instruction::Add(operand) => write!(f, "add {}", operand),
+            Instruction::Sub(operand) => write!(f, "sub {}", operand),
+            Instruction::Mul(operand) => write!(f, "mul {}", operand),
+            Instruction::Div(operand) => write!(f, "div {}", operand),
+            Instruction::Goto(label) => write!(f, "goto {}", label),
+            Instruction::Jzero(label) => write!(f, "jzero {}", label),
+            Instruction::Jnzero(label) => write!(f, "jnzero {}", label),
+            Instruction::End => write!(f, "end"),
+        }
+    }
+}
+
+impl std::fmt::Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Immediate(value) => write!(f, "#{}", value),
+            Operand::DirectAddress(address) => write!(f, "{}", address),
+            Operand::IndirectAddress(address) => write!(f, "*{}", address),
+        }
+    }
+}
+
+impl std::fmt::Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Address::Direct(address) => write!(f, "{}", address),
+            Address::Indirect(address) => write!(f, "*{}", address),
+        }
+    }
+}
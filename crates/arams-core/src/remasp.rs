@@ -0,0 +1,103 @@
+use std::{collections::HashMap, num::ParseIntError};
+
+use crate::{
+    IntoSourceCode, compile,
+    disassembler::disassemble_with,
+    errors::{CompileError, CompileErrorKind},
+    program::{Instruction, Program},
+};
+
+pub fn import(
+    source_code: impl IntoSourceCode,
+) -> Result<(Program, HashMap<usize, u64>), Vec<CompileError>> {
+    let mut registers = HashMap::new();
+    let mut errors = Vec::new();
+
+    let lines: Vec<String> = source_code
+        .into_lines()
+        .iter()
+        .enumerate()
+        .map(|(line_number, line)| {
+            let (code, comment) = match line.find("//") {
+                Some(index) => (&line[..index], Some(&line[index + 2..])),
+                None => (line.as_str(), None),
+            };
+
+            if code.trim().is_empty()
+                && let Some(comment) = comment
+            {
+                match register_initialisation_from_str(comment) {
+                    Some(Ok((register, value))) => {
+                        registers.insert(register, value);
+                    }
+                    Some(Err(_)) => {
+                        errors.push(CompileError::new(
+                            line_number + 1,
+                            CompileErrorKind::InvalidArgument,
+                            comment.trim().to_string(),
+                        ));
+                    }
+                    None => {}
+                }
+            }
+
+            normalize_code(code)
+        })
+        .collect();
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let program = compile(lines)?;
+
+    Ok((program, registers))
+}
+
+pub fn export(program: &Program, registers: &HashMap<usize, u64>) -> String {
+    let mut output = String::new();
+
+    // Remasp has no input directives, they are exported as register initialisations
+    let registers = program.initial_registers(registers);
+    let mut registers: Vec<_> = registers.iter().collect();
+    registers.sort_by_key(|(register, _)| *register);
+    for (register, value) in &registers {
+        output.push_str(&format!("// R{} = {}\n", register, value));
+    }
+    if !registers.is_empty() {
+        output.push('\n');
+    }
+
+    output.push_str(&disassemble_with(program, instruction_to_remasp));
+
+    output
+}
+
+fn register_initialisation_from_str(comment: &str) -> Option<Result<(usize, u64), ParseIntError>> {
+    let (register, value) = comment.trim().split_once('=')?;
+    let register = register
+        .trim()
+        .strip_prefix(['R', 'r'])?
+        .parse::<usize>()
+        .ok()?;
+
+    Some(value.trim().parse::<u64>().map(|value| (register, value)))
+}
+
+// Remasp allows labels without whitespace before the instruction (e.g. `loop:load 1`)
+fn normalize_code(code: &str) -> String {
+    match code.split_once(':') {
+        Some((label, rest)) if !label.trim().is_empty() && !label.trim().contains(' ') => {
+            format!("{}: {}", label.trim(), rest.trim())
+        }
+        _ => code.trim().to_string(),
+    }
+}
+
+fn instruction_to_remasp(instruction: &Instruction) -> String {
+    let instruction = instruction.to_string();
+    match instruction.split_once(' ') {
+        Some((keyword, argument)) => format!("{} {}", keyword.to_uppercase(), argument),
+        None => instruction.to_uppercase(),
+    }
+}
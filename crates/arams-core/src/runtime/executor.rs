@@ -3,103 +3,102 @@ use std::collections::HashMap;
 use crate::{
     errors::RuntimeError,
     program::{Address, Instruction, Operand, Program},
-    runtime::machine::Machine,
+    runtime::machine::{Machine, StopReason},
 };
 
 pub fn execute(
-    mut program: Program,
+    program: Program,
     registers: Option<HashMap<usize, u64>>,
 ) -> Result<Machine, RuntimeError> {
-    let mut machine = match registers {
-        Some(regs) => Machine::new_with_preseeded_registers(regs),
-        None => Machine::new(),
-    };
+    let machine = Machine::new_with_preseeded_registers(
+        program.initial_registers(&registers.unwrap_or_default()),
+    );
+
+    resume(&program, machine)
+}
 
+pub fn resume(program: &Program, mut machine: Machine) -> Result<Machine, RuntimeError> {
     while !machine.is_stopped() {
-        let instruction = match program.fetch() {
-            Some(l) => l,
-            None => break,
-        };
+        step(program, &mut machine)?;
+    }
 
-        match instruction {
-            Instruction::Load(operand) => {
-                machine.load(match operand {
-                    Operand::Immediate(value) => value,
-                    Operand::DirectAddress(addr) => *machine.get_register_value(addr),
-                    Operand::IndirectAddress(addr) => {
-                        *machine.get_register_value(*machine.get_register_value(addr) as usize)
-                    }
-                })?;
-            }
-            Instruction::Store(address) => {
-                machine.store(match address {
-                    Address::Direct(addr) => addr,
-                    Address::Indirect(addr) => *machine.get_register_value(addr) as usize,
-                })?;
-            }
-            Instruction::Add(operand) => {
-                machine.add(match operand {
-                    Operand::Immediate(value) => value,
-                    Operand::DirectAddress(addr) => *machine.get_register_value(addr),
-                    Operand::IndirectAddress(addr) => {
-                        *machine.get_register_value(*machine.get_register_value(addr) as usize)
-                    }
-                })?;
-            }
-            Instruction::Sub(operand) => {
-                machine.sub(match operand {
-                    Operand::Immediate(value) => value,
-                    Operand::DirectAddress(addr) => *machine.get_register_value(addr),
-                    Operand::IndirectAddress(addr) => {
-                        *machine.get_register_value(*machine.get_register_value(addr) as usize)
-                    }
-                })?;
-            }
-            Instruction::Mul(operand) => {
-                machine.mul(match operand {
-                    Operand::Immediate(value) => value,
-                    Operand::DirectAddress(addr) => *machine.get_register_value(addr),
-                    Operand::IndirectAddress(addr) => {
-                        *machine.get_register_value(*machine.get_register_value(addr) as usize)
-                    }
-                })?;
-            }
-            Instruction::Div(operand) => {
-                machine.div(match operand {
-                    Operand::Immediate(value) => value,
-                    Operand::DirectAddress(addr) => *machine.get_register_value(addr),
-                    Operand::IndirectAddress(addr) => {
-                        *machine.get_register_value(*machine.get_register_value(addr) as usize)
-                    }
-                })?;
-            }
-            Instruction::Goto(label) => {
-                program.goto(&label)?;
-                continue;
-            }
-            Instruction::Jzero(label) => {
-                if machine.get_accumulator() == 0 {
-                    program.goto(&label)?;
-                    continue;
-                }
-            }
-            Instruction::Jnzero(label) => {
-                if machine.get_accumulator() != 0 {
-                    program.goto(&label)?;
-                    continue;
-                }
+    Ok(machine)
+}
+
+pub fn step(program: &Program, machine: &mut Machine) -> Result<(), RuntimeError> {
+    let program_counter = machine.get_program_counter();
+
+    let instruction = match program.fetch(program_counter) {
+        Some(instruction) => instruction.clone(),
+        // reaching the end of the program stops the machine, no instruction is executed
+        None => return machine.stop(StopReason::EndOfProgram),
+    };
+
+    execute_instruction(program, machine, instruction)
+        .map_err(|err| err.at_line(program.source_line(program_counter)))?;
+    machine.count_step();
+
+    Ok(())
+}
+
+fn execute_instruction(
+    program: &Program,
+    machine: &mut Machine,
+    instruction: Instruction,
+) -> Result<(), RuntimeError> {
+    match instruction {
+        Instruction::Load(operand) => {
+            machine.load(operand_value(machine, operand)?)?;
+        }
+        Instruction::Store(address) => {
+            machine.store(match address {
+                Address::Direct(addr) => addr,
+                Address::Indirect(addr) => machine.read_register(addr)? as usize,
+            })?;
+        }
+        Instruction::Add(operand) => {
+            machine.add(operand_value(machine, operand)?)?;
+        }
+        Instruction::Sub(operand) => {
+            machine.sub(operand_value(machine, operand)?)?;
+        }
+        Instruction::Mul(operand) => {
+            machine.mul(operand_value(machine, operand)?)?;
+        }
+        Instruction::Div(operand) => {
+            machine.div(operand_value(machine, operand)?)?;
+        }
+        Instruction::Goto(label) => {
+            return machine.jump(program.resolve_label(&label)?);
+        }
+        Instruction::Jzero(label) => {
+            if machine.get_accumulator() == 0 {
+                return machine.jump(program.resolve_label(&label)?);
             }
-            Instruction::End => {
-                machine.end()?;
+        }
+        Instruction::Jnzero(label) => {
+            if machine.get_accumulator() != 0 {
+                return machine.jump(program.resolve_label(&label)?);
             }
         }
-
-        program.advance();
+        Instruction::End => {
+            machine.end()?;
+        }
     }
 
     if !machine.is_stopped() {
-        machine.end()?;
+        machine.advance()?;
     }
 
-    Ok(machine)
+    Ok(())
+}
+
+fn operand_value(machine: &Machine, operand: Operand) -> Result<u64, RuntimeError> {
+    match operand {
+        Operand::Immediate(value) => Ok(value),
+        Operand::DirectAddress(addr) => machine.read_register(addr),
+        Operand::IndirectAddress(addr) => {
+            machine.read_register(machine.read_register(addr)? as usize)
+        }
+    }
 }
@@ -2,11 +2,77 @@ use std::collections::HashMap;
 
 use crate::errors::RuntimeError;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum StopReason {
+    End,
+    EndOfProgram,
+    DivisionByZero,
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StopReason::End => write!(f, "end instruction reached"),
+            StopReason::EndOfProgram => write!(f, "end of program reached"),
+            StopReason::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+// how `add`, `sub` and `mul` behave when the result does not fit into a register
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ArithmeticMode {
+    #[default]
+    Wrapping,
+    // results are clamped to `0..=u64::MAX`, `sub` is the modified subtraction of the RAM model
+    Saturating,
+    // overflows are runtime errors
+    Checked,
+}
+
+impl std::str::FromStr for ArithmeticMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wrapping" => Ok(ArithmeticMode::Wrapping),
+            "saturating" => Ok(ArithmeticMode::Saturating),
+            "checked" => Ok(ArithmeticMode::Checked),
+            _ => Err(format!(
+                "Invalid arithmetic mode '{}', expected one of wrapping, saturating, checked",
+                s
+            )),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Machine {
     accumulator: u64,
     registers: HashMap<usize, u64>,
+    program_counter: usize,
     running: bool,
+    // snapshots created before these fields existed are still accepted
+    #[cfg_attr(feature = "serde", serde(default))]
+    steps: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    stop_reason: Option<StopReason>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    arithmetic_mode: ArithmeticMode,
+    // reading a register that has never been written is a runtime error
+    #[cfg_attr(feature = "serde", serde(default))]
+    strict_reads: bool,
 }
 
 impl Machine {
@@ -18,7 +84,12 @@ impl Machine {
         Self {
             accumulator: 0,
             registers,
+            program_counter: 0,
             running: true,
+            steps: 0,
+            stop_reason: None,
+            arithmetic_mode: ArithmeticMode::default(),
+            strict_reads: false,
         }
     }
 
@@ -34,13 +105,57 @@ impl Machine {
         self.registers.get(&register).unwrap_or(&0)
     }
 
+    pub fn read_register(&self, register: usize) -> Result<u64, RuntimeError> {
+        match self.registers.get(&register) {
+            Some(value) => Ok(*value),
+            None if self.strict_reads => Err(RuntimeError::UninitializedRegister {
+                register,
+                line: None,
+            }),
+            None => Ok(0),
+        }
+    }
+
+    pub fn get_arithmetic_mode(&self) -> ArithmeticMode {
+        self.arithmetic_mode
+    }
+
+    pub fn set_arithmetic_mode(&mut self, arithmetic_mode: ArithmeticMode) {
+        self.arithmetic_mode = arithmetic_mode;
+    }
+
+    pub fn has_strict_reads(&self) -> bool {
+        self.strict_reads
+    }
+
+    pub fn set_strict_reads(&mut self, strict_reads: bool) {
+        self.strict_reads = strict_reads;
+    }
+
+    pub fn get_program_counter(&self) -> usize {
+        self.program_counter
+    }
+
     pub fn is_stopped(&self) -> bool {
         !self.running
     }
 
+    // number of executed instructions
+    pub fn get_steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn get_stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason
+    }
+
+    pub(crate) fn count_step(&mut self) {
+        self.steps += 1;
+    }
+
     pub fn set_register_value(&mut self, register: usize, value: u64) -> Result<(), RuntimeError> {
         if !self.running {
-            return Err(RuntimeError::MachineStopped);
+            return Err(RuntimeError::MachineStopped { line: None });
         }
 
         self.registers.insert(register, value);
@@ -50,51 +165,72 @@ impl Machine {
 
     pub fn add(&mut self, value: u64) -> Result<(), RuntimeError> {
         if !self.running {
-            return Err(RuntimeError::MachineStopped);
+            return Err(RuntimeError::MachineStopped { line: None });
         }
 
-        self.accumulator = self.accumulator.wrapping_add(value);
+        self.accumulator = match self.arithmetic_mode {
+            ArithmeticMode::Wrapping => self.accumulator.wrapping_add(value),
+            ArithmeticMode::Saturating => self.accumulator.saturating_add(value),
+            ArithmeticMode::Checked => self
+                .accumulator
+                .checked_add(value)
+                .ok_or(RuntimeError::ArithmeticOverflow { line: None })?,
+        };
 
         Ok(())
     }
 
     pub fn sub(&mut self, value: u64) -> Result<(), RuntimeError> {
         if !self.running {
-            return Err(RuntimeError::MachineStopped);
+            return Err(RuntimeError::MachineStopped { line: None });
         }
 
-        self.accumulator = self.accumulator.wrapping_sub(value);
+        self.accumulator = match self.arithmetic_mode {
+            ArithmeticMode::Wrapping => self.accumulator.wrapping_sub(value),
+            ArithmeticMode::Saturating => self.accumulator.saturating_sub(value),
+            ArithmeticMode::Checked => self
+                .accumulator
+                .checked_sub(value)
+                .ok_or(RuntimeError::ArithmeticOverflow { line: None })?,
+        };
 
         Ok(())
     }
 
     pub fn mul(&mut self, value: u64) -> Result<(), RuntimeError> {
         if !self.running {
-            return Err(RuntimeError::MachineStopped);
+            return Err(RuntimeError::MachineStopped { line: None });
         }
 
-        self.accumulator = self.accumulator.wrapping_mul(value);
+        self.accumulator = match self.arithmetic_mode {
+            ArithmeticMode::Wrapping => self.accumulator.wrapping_mul(value),
+            ArithmeticMode::Saturating => self.accumulator.saturating_mul(value),
+            ArithmeticMode::Checked => self
+                .accumulator
+                .checked_mul(value)
+                .ok_or(RuntimeError::ArithmeticOverflow { line: None })?,
+        };
 
         Ok(())
     }
 
     pub fn div(&mut self, value: u64) -> Result<(), RuntimeError> {
         if !self.running {
-            return Err(RuntimeError::MachineStopped);
+            return Err(RuntimeError::MachineStopped { line: None });
         }
 
         if value == 0 {
-            self.running = false;
-        } else {
-            self.accumulator = self.accumulator.wrapping_div(value);
+            return self.stop(StopReason::DivisionByZero);
         }
 
+        self.accumulator = self.accumulator.wrapping_div(value);
+
         Ok(())
     }
 
     pub fn load(&mut self, value: u64) -> Result<(), RuntimeError> {
         if !self.running {
-            return Err(RuntimeError::MachineStopped);
+            return Err(RuntimeError::MachineStopped { line: None });
         }
 
         self.accumulator = value;
@@ -104,7 +240,7 @@ impl Machine {
 
     pub fn store(&mut self, address: usize) -> Result<(), RuntimeError> {
         if !self.running {
-            return Err(RuntimeError::MachineStopped);
+            return Err(RuntimeError::MachineStopped { line: None });
         }
 
         self.registers.insert(address, self.accumulator);
@@ -112,12 +248,37 @@ impl Machine {
         Ok(())
     }
 
+    pub fn advance(&mut self) -> Result<(), RuntimeError> {
+        if !self.running {
+            return Err(RuntimeError::MachineStopped { line: None });
+        }
+
+        self.program_counter += 1;
+
+        Ok(())
+    }
+
+    pub fn jump(&mut self, program_counter: usize) -> Result<(), RuntimeError> {
+        if !self.running {
+            return Err(RuntimeError::MachineStopped { line: None });
+        }
+
+        self.program_counter = program_counter;
+
+        Ok(())
+    }
+
     pub fn end(&mut self) -> Result<(), RuntimeError> {
+        self.stop(StopReason::End)
+    }
+
+    pub fn stop(&mut self, reason: StopReason) -> Result<(), RuntimeError> {
         if !self.running {
-            return Err(RuntimeError::MachineStopped);
+            return Err(RuntimeError::MachineStopped { line: None });
         }
 
         self.running = false;
+        self.stop_reason = Some(reason);
 
         Ok(())
     }
@@ -128,7 +289,12 @@ impl Default for Machine {
         Self {
             accumulator: 0,
             registers: HashMap::new(),
+            program_counter: 0,
             running: true,
+            steps: 0,
+            stop_reason: None,
+            arithmetic_mode: ArithmeticMode::default(),
+            strict_reads: false,
         }
     }
 }
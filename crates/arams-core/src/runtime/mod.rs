@@ -1,5 +1,7 @@
 mod executor;
 mod machine;
+mod snapshot;
 
-pub use executor::execute;
-pub use machine::Machine;
+pub use executor::{execute, resume, step};
+pub use machine::{ArithmeticMode, Machine, StopReason};
+pub use snapshot::Snapshot;
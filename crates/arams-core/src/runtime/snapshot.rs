@@ -0,0 +1,34 @@
+use crate::{
+    errors::RuntimeError,
+    program::Program,
+    runtime::{executor, machine::Machine},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    program: Program,
+    machine: Machine,
+}
+
+impl Snapshot {
+    pub fn new(program: Program, machine: Machine) -> Self {
+        Self { program, machine }
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    pub fn machine(&self) -> &Machine {
+        &self.machine
+    }
+
+    pub fn step(&mut self) -> Result<(), RuntimeError> {
+        executor::step(&self.program, &mut self.machine)
+    }
+
+    pub fn resume(self) -> Result<Machine, RuntimeError> {
+        executor::resume(&self.program, self.machine)
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use crate::{
+    errors::RuntimeError,
+    program::Program,
+    runtime::{Machine, step},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TestCase {
+    name: String,
+    inputs: HashMap<usize, u64>,
+    expected_accumulator: Option<u64>,
+    expected_registers: HashMap<usize, u64>,
+    max_steps: Option<u64>,
+}
+
+impl TestCase {
+    pub fn new(
+        name: String,
+        inputs: HashMap<usize, u64>,
+        expected_accumulator: Option<u64>,
+        expected_registers: HashMap<usize, u64>,
+        max_steps: Option<u64>,
+    ) -> Self {
+        Self {
+            name,
+            inputs,
+            expected_accumulator,
+            expected_registers,
+            max_steps,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn inputs(&self) -> &HashMap<usize, u64> {
+        &self.inputs
+    }
+
+    pub fn expected_accumulator(&self) -> Option<u64> {
+        self.expected_accumulator
+    }
+
+    pub fn expected_registers(&self) -> &HashMap<usize, u64> {
+        &self.expected_registers
+    }
+
+    pub fn max_steps(&self) -> Option<u64> {
+        self.max_steps
+    }
+}
+
+// the same syntax as the `// @test` directive
+impl std::fmt::Display for TestCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut inputs: Vec<_> = self.inputs.iter().collect();
+        inputs.sort_by_key(|(register, _)| *register);
+        let inputs: Vec<String> = inputs
+            .into_iter()
+            .map(|(register, value)| format!("r{} = {}", register, value))
+            .collect();
+
+        let mut expected_registers: Vec<_> = self.expected_registers.iter().collect();
+        expected_registers.sort_by_key(|(register, _)| *register);
+        let expectations: Vec<String> = self
+            .expected_accumulator
+            .map(|value| format!("acc = {}", value))
+            .into_iter()
+            .chain(
+                expected_registers
+                    .into_iter()
+                    .map(|(register, value)| format!("r{} = {}", register, value)),
+            )
+            .collect();
+
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.name,
+            inputs.join(", "),
+            expectations.join(", ")
+        )?;
+        if let Some(max_steps) = self.max_steps {
+            write!(f, "; max_steps = {}", max_steps)?;
+        }
+        Ok(())
+    }
+}
+
+// a value of the machine that differs from the expectation, `register` is `None` for the accumulator
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mismatch {
+    register: Option<usize>,
+    expected: u64,
+    actual: u64,
+}
+
+impl Mismatch {
+    pub fn register(&self) -> Option<usize> {
+        self.register
+    }
+
+    pub fn expected(&self) -> u64 {
+        self.expected
+    }
+
+    pub fn actual(&self) -> u64 {
+        self.actual
+    }
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.register {
+            Some(register) => write!(f, "r{}", register)?,
+            None => write!(f, "acc")?,
+        }
+        write!(f, ": expected {}, got {}", self.expected, self.actual)
+    }
+}
+
+#[derive(Debug)]
+pub enum TestOutcome {
+    Passed {
+        steps: u64,
+    },
+    Failed {
+        steps: u64,
+        mismatches: Vec<Mismatch>,
+    },
+    StepLimitExceeded {
+        max_steps: u64,
+    },
+    RuntimeError(RuntimeError),
+}
+
+impl TestOutcome {
+    pub fn is_passed(&self) -> bool {
+        matches!(self, TestOutcome::Passed { .. })
+    }
+}
+
+impl std::fmt::Display for TestOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TestOutcome::Passed { steps } => write!(f, "ok ({} steps)", steps),
+            TestOutcome::Failed { steps, mismatches } => {
+                write!(f, "FAILED ({} steps)", steps)?;
+                for mismatch in mismatches {
+                    write!(f, "\n    {}", mismatch)?;
+                }
+                Ok(())
+            }
+            TestOutcome::StepLimitExceeded { max_steps } => {
+                write!(f, "FAILED\n    did not stop within {} steps", max_steps)
+            }
+            TestOutcome::RuntimeError(err) => write!(f, "FAILED\n    {}", err),
+        }
+    }
+}
+
+// the step bound of the test case takes precedence over `max_steps`
+pub fn run_test(program: &Program, test_case: &TestCase, max_steps: Option<u64>) -> TestOutcome {
+    let max_steps = test_case.max_steps.or(max_steps);
+    let mut machine =
+        Machine::new_with_preseeded_registers(program.initial_registers(&test_case.inputs));
+
+    while !machine.is_stopped() {
+        if let Some(max_steps) = max_steps
+            && machine.get_steps() >= max_steps
+        {
+            return TestOutcome::StepLimitExceeded { max_steps };
+        }
+        if let Err(err) = step(program, &mut machine) {
+            return TestOutcome::RuntimeError(err);
+        }
+    }
+
+    let mut mismatches = Vec::new();
+    if let Some(expected) = test_case.expected_accumulator
+        && expected != machine.get_accumulator()
+    {
+        mismatches.push(Mismatch {
+            register: None,
+            expected,
+            actual: machine.get_accumulator(),
+        });
+    }
+
+    let mut expected_registers: Vec<_> = test_case.expected_registers.iter().collect();
+    expected_registers.sort_by_key(|(register, _)| *register);
+    for (&register, &expected) in expected_registers {
+        let actual = *machine.get_register_value(register);
+        if actual != expected {
+            mismatches.push(Mismatch {
+                register: Some(register),
+                expected,
+                actual,
+            });
+        }
+    }
+
+    if mismatches.is_empty() {
+        TestOutcome::Passed {
+            steps: machine.get_steps(),
+        }
+    } else {
+        TestOutcome::Failed {
+            steps: machine.get_steps(),
+            mismatches,
+        }
+    }
+}
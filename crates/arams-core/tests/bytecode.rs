@@ -0,0 +1,128 @@
+use arams_core::{BytecodeError, bytecode, compile, disassemble, execute};
+use std::collections::HashMap;
+
+const FACTORIAL_PROGRAM: &str = "load 1
+    jzero return_one
+    sub #1
+    jzero return_one
+    load 1
+    store 2
+    loop: load 1
+    sub #1
+    jzero break
+    store 1
+    mul 2
+    store 2
+    goto loop
+    return_one: load #1
+    end
+    break: load 2
+    end";
+
+#[test]
+fn factorial_program_round_trips_with_labels() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let bytes = bytecode::encode(&program, true).unwrap();
+    assert!(bytecode::is_bytecode(&bytes));
+    assert_eq!(bytecode::decode(&bytes).unwrap(), program);
+}
+
+#[test]
+fn factorial_program_executes_without_labels() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let bytes = bytecode::encode(&program, false).unwrap();
+    assert!(bytes.len() < bytecode::encode(&program, true).unwrap().len());
+
+    let decoded = bytecode::decode(&bytes).unwrap();
+    assert!(disassemble(&decoded).contains("goto L6"));
+
+    let machine = execute(decoded, Some(HashMap::from([(1, 5)]))).unwrap();
+    assert_eq!(machine.get_accumulator(), 120);
+}
+
+#[test]
+fn test_cases_round_trip_without_debug_info() {
+    let program = compile(format!(
+        "// @test five: r1 = 5 -> acc = 120, r2 = 120; max_steps = 100\n// @test zero: r1 = 0 -> acc = 1\n{}",
+        FACTORIAL_PROGRAM
+    ))
+    .unwrap();
+    let decoded = bytecode::decode(&bytecode::encode(&program, false).unwrap()).unwrap();
+
+    assert_eq!(decoded.test_cases(), program.test_cases());
+    assert_ne!(decoded, program.clone().without_directives());
+}
+
+#[test]
+fn unknown_labels_cannot_be_encoded() {
+    let program = compile("goto nowhere").unwrap();
+    assert!(matches!(
+        bytecode::encode(&program, true),
+        Err(BytecodeError::UnknownLabel(label)) if label == "nowhere"
+    ));
+}
+
+#[test]
+fn malformed_bytecode_is_rejected() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let bytes = bytecode::encode(&program, true).unwrap();
+
+    assert!(matches!(
+        bytecode::decode(b"load 1"),
+        Err(BytecodeError::InvalidMagic)
+    ));
+    assert!(matches!(
+        bytecode::decode(&bytes[..bytes.len() - 1]),
+        Err(BytecodeError::UnexpectedEnd)
+    ));
+
+    let mut wrong_version = bytes.clone();
+    wrong_version[4] = 0xff;
+    assert!(matches!(
+        bytecode::decode(&wrong_version),
+        Err(BytecodeError::UnsupportedVersion(_))
+    ));
+}
+
+#[test]
+fn instruction_counts_larger_than_the_file_are_rejected() {
+    assert!(matches!(
+        bytecode::decode(b"RAMC\x01\x00\xff\xff\xff\xff"),
+        Err(BytecodeError::UnexpectedEnd)
+    ));
+
+    // two instructions announced, one present
+    let program = compile("load #1\nend").unwrap();
+    let bytes = bytecode::encode(&program, false).unwrap();
+    assert!(matches!(
+        bytecode::decode(&bytes[..bytes.len() - 10]),
+        Err(BytecodeError::UnexpectedEnd)
+    ));
+}
+
+#[test]
+fn source_line_tables_have_to_match_the_instructions() {
+    let program = compile("load #1\nend").unwrap();
+    let mut bytes = bytecode::encode(&program, false).unwrap();
+    // a source lines section with a single line for two instructions
+    bytes.extend_from_slice(&[2, 4, 0, 0, 0, 1, 0, 0, 0]);
+
+    assert!(matches!(
+        bytecode::decode(&bytes),
+        Err(BytecodeError::InvalidSourceLines)
+    ));
+}
+
+#[test]
+fn end_takes_no_argument() {
+    let program = compile("end").unwrap();
+    let mut bytes = bytecode::encode(&program, false).unwrap();
+    assert!(bytecode::decode(&bytes).is_ok());
+
+    // the argument kind follows the magic, the version, the instruction count and the opcode
+    bytes[11] = 1;
+    assert!(matches!(
+        bytecode::decode(&bytes),
+        Err(BytecodeError::InvalidArgument(1))
+    ));
+}
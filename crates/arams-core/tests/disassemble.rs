@@ -0,0 +1,68 @@
+use arams_core::{Address, Instruction, Operand, compile, disassemble};
+
+const FACTORIAL_PROGRAM: &str = "load 1
+    jzero return_one
+    sub #1
+    jzero return_one
+    load 1
+    store 2
+    loop: load 1
+    sub #1
+    jzero break
+    store 1
+    mul 2
+    store 2
+    goto loop
+    return_one: load #1
+    end
+    break: load 2
+    end";
+
+#[test]
+fn instructions_display_as_source() {
+    assert_eq!(
+        Instruction::Load(Operand::Immediate(3)).to_string(),
+        "load #3"
+    );
+    assert_eq!(
+        Instruction::Add(Operand::DirectAddress(2)).to_string(),
+        "add 2"
+    );
+    assert_eq!(
+        Instruction::Div(Operand::IndirectAddress(4)).to_string(),
+        "div *4"
+    );
+    assert_eq!(
+        Instruction::Store(Address::Indirect(1)).to_string(),
+        "store *1"
+    );
+    assert_eq!(
+        Instruction::Jnzero("loop".to_string()).to_string(),
+        "jnzero loop"
+    );
+    assert_eq!(Instruction::End.to_string(), "end");
+}
+
+#[test]
+fn factorial_program_disassembles_to_canonical_source() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let source = disassemble(&program);
+    assert_eq!(source, include_str!("../../../examples/factorial.txt"));
+}
+
+#[test]
+fn factorial_program_round_trips() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    assert_eq!(compile(disassemble(&program)).unwrap(), program);
+}
+
+#[test]
+fn labels_at_the_same_position_and_behind_the_end_round_trip() {
+    let program = compile("a: b: load *1\nstore *2\nc:\nd: jzero a\ngoto e\ne:").unwrap();
+    let source = disassemble(&program);
+    assert_eq!(
+        source,
+        "a:\nb: load *1\n   store *2\nc:\nd: jzero a\n   goto e\ne:\n"
+    );
+    assert_eq!(compile(source).unwrap(), program);
+}
@@ -0,0 +1,102 @@
+use arams_core::lsp::{Document, LSPError, Position, Range, TextEdit, analyze};
+
+const PROGRAM: &str = "load 1
+jzero zero
+loop: sub #1
+jnzero loop
+zero: end";
+
+fn edit(document: &mut Document, start: (usize, usize), end: (usize, usize), text: &str) {
+    document.edit(&TextEdit::new(
+        Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
+        text,
+    ));
+}
+
+// the incremental analysis has to match a fresh analysis of the whole text
+fn assert_up_to_date(document: &Document) {
+    let fresh = analyze(document.text());
+    let tokens = |lines: &[Vec<arams_core::lsp::LSPToken>]| -> Vec<String> {
+        lines
+            .iter()
+            .flatten()
+            .map(|token| format!("{} {:?} {:?}", token.kind(), token.lexeme(), token.range()))
+            .collect()
+    };
+    assert_eq!(tokens(document.tokens()), tokens(&fresh));
+
+    let messages = |diagnostics: Vec<arams_core::lsp::Diagnostic>| -> Vec<String> {
+        diagnostics
+            .iter()
+            .map(|diagnostic| format!("{:?} {}", diagnostic.range(), diagnostic.message()))
+            .collect()
+    };
+    assert_eq!(
+        messages(document.diagnostics()),
+        messages(arams_core::lsp::diagnostics(&fresh))
+    );
+}
+
+#[test]
+fn edits_within_a_line_are_applied() {
+    let mut document = Document::new(PROGRAM);
+
+    edit(&mut document, (0, 5), (0, 6), "*2");
+    assert_eq!(document.lines()[0], "load *2");
+    assert_up_to_date(&document);
+
+    // positions are counted in UTF-16 code units
+    edit(&mut document, (0, 7), (0, 7), " // ü𝄞");
+    edit(&mut document, (0, 14), (0, 14), "!");
+    assert_eq!(document.lines()[0], "load *2 // ü𝄞!");
+    assert_up_to_date(&document);
+}
+
+#[test]
+fn inserted_and_removed_lines_move_the_following_tokens() {
+    let mut document = Document::new(PROGRAM);
+
+    edit(&mut document, (1, 0), (1, 0), "store 2\nstore 3\n");
+    assert_eq!(document.lines().len(), 7);
+    assert_up_to_date(&document);
+    assert_eq!(
+        document.definition(Position::new(5, 9)),
+        Some(Range::new(Position::new(4, 0), Position::new(4, 4)))
+    );
+
+    edit(&mut document, (0, 6), (4, 0), "");
+    assert_eq!(document.lines()[0], "load 1loop: sub #1");
+    assert_up_to_date(&document);
+
+    // positions behind the end are clamped, the old last line gets a newline token
+    edit(&mut document, (9, 0), (9, 0), "\nend");
+    assert_eq!(document.lines().len(), 4);
+    assert_up_to_date(&document);
+}
+
+#[test]
+fn label_diagnostics_follow_the_edits() {
+    let mut document = Document::new(PROGRAM);
+    assert!(document.diagnostics().is_empty());
+
+    // removing a label breaks the jumps to it
+    edit(&mut document, (4, 0), (4, 6), "");
+    assert!(matches!(
+        document.diagnostics()[0].error(),
+        LSPError::LabelNotFound(label) if label == "zero"
+    ));
+    assert_up_to_date(&document);
+
+    // a definition above an existing one turns the existing one into the duplicate
+    edit(&mut document, (4, 0), (4, 0), "zero: ");
+    edit(&mut document, (0, 0), (0, 0), "loop: ");
+    let diagnostics = document.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range().start(), Position::new(2, 0));
+    assert_eq!(
+        document.labels().definition("loop"),
+        Some(Range::new(Position::new(0, 0), Position::new(0, 4)))
+    );
+    assert_eq!(document.labels().references("loop").len(), 1);
+    assert_up_to_date(&document);
+}
@@ -0,0 +1,34 @@
+use arams_core::{compile, disassemble, lsp::format};
+
+#[test]
+fn canonical_source_stays_unchanged() {
+    let source = include_str!("../../../examples/factorial.txt");
+    assert_eq!(format(source), source);
+}
+
+#[test]
+fn labels_are_aligned_and_keywords_lowercased() {
+    let source = "  LOAD 1\nloop:   Sub #1\n\n\n   JNZERO loop\nexit: END";
+    assert_eq!(
+        format(source),
+        "      load 1\nloop: sub #1\n\n      jnzero loop\nexit: end\n"
+    );
+}
+
+#[test]
+fn comments_and_label_only_lines_are_kept() {
+    let source = "// header  comment\n\nstart:   load #1 //  keep   spacing\na: b: goto a\nend:";
+    assert_eq!(
+        format(source),
+        "// header  comment\n\nstart: load #1 //  keep   spacing\na:\nb:     goto a\nend:\n"
+    );
+}
+
+#[test]
+fn formatted_program_compiles_to_the_same_program() {
+    let source = "load 1\n  jzero   zero // done\nstore 2\nzero: END";
+    let program = compile(source).unwrap();
+    let formatted = format(source);
+    assert_eq!(compile(formatted.as_str()).unwrap(), program);
+    assert_eq!(format(disassemble(&program)), disassemble(&program));
+}
@@ -0,0 +1,83 @@
+use arams_core::{CompileErrorKind, bytecode, compile, disassemble, execute, optimize, remasp};
+use std::collections::HashMap;
+
+const FACTORIAL_PROGRAM: &str = "// @input r1 = 5
+    load 1
+    jzero return_one
+    sub #1
+    jzero return_one
+    load 1
+    store 2
+    loop: load 1
+    sub #1
+    jzero break
+    store 1
+    mul 2
+    store 2
+    goto loop
+    return_one: load #1
+    end
+    break: load 2
+    end";
+
+#[test]
+fn input_directives_are_stored_on_the_program() {
+    let program = compile("//@input r1 = 5, 2=3\nload 1 // @input R4 = 1\nend").unwrap();
+    assert_eq!(program.inputs(), &HashMap::from([(1, 5), (2, 3), (4, 1)]));
+}
+
+#[test]
+fn inputs_are_defaults_that_registers_override() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let machine = execute(program.clone(), None).unwrap();
+    assert_eq!(machine.get_accumulator(), 120);
+
+    let machine = execute(program, Some(HashMap::from([(1, 3)]))).unwrap();
+    assert_eq!(machine.get_accumulator(), 6);
+}
+
+#[test]
+fn invalid_input_directives_are_compile_errors() {
+    let errors = compile("// @input r1 = x\nend").unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line_number(), 1);
+    assert!(matches!(
+        errors[0].kind(),
+        CompileErrorKind::InvalidDirective
+    ));
+
+    // other comments starting with @input are not directives
+    assert!(
+        compile("// @inputs are registers\nend")
+            .unwrap()
+            .inputs()
+            .is_empty()
+    );
+}
+
+#[test]
+fn inputs_survive_disassembly_bytecode_and_optimization() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+
+    let source = disassemble(&program);
+    assert!(source.starts_with("// @input r1 = 5\n\n"));
+    assert_eq!(compile(source).unwrap(), program);
+
+    let bytes = bytecode::encode(&program, false).unwrap();
+    assert_eq!(bytecode::decode(&bytes).unwrap().inputs(), program.inputs());
+
+    let (optimized, _) = optimize(&program);
+    assert_eq!(optimized.inputs(), program.inputs());
+
+    assert!(remasp::export(&program, &HashMap::new()).starts_with("// R1 = 5\n"));
+}
+
+#[test]
+fn directives_can_be_removed_from_a_program() {
+    let program = compile("// @input r1 = 5\n// @test five: -> acc = 5\nload 1\nend")
+        .unwrap()
+        .without_directives();
+    assert!(program.inputs().is_empty());
+    assert!(program.test_cases().is_empty());
+    assert_eq!(execute(program, None).unwrap().get_accumulator(), 0);
+}
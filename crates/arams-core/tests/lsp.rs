@@ -1,4 +1,13 @@
-use arams_core::lsp::{LSPError, LSPTokenKind, analyze};
+use arams_core::{
+    Machine,
+    lsp::{
+        CodeAction, CompletionKind, DiagnosticSeverity, FoldingRangeKind, LSPError, LSPTokenKind,
+        Position, Range, SEMANTIC_TOKEN_TYPES, analyze, code_actions, completions, definition,
+        diagnostics, document_symbols, folding_ranges, hover, inlay_hints, semantic_tokens,
+        token_at,
+    },
+};
+use std::collections::HashMap;
 
 const FACTORIAL_PROGRAM: &str = "load 1
     store // no argument here to trigger an error
@@ -43,3 +52,460 @@ fn factorial_program_gets_analyzed() {
         LSPError::InvalidArgument(_)
     ));
 }
+
+#[test]
+fn tokens_know_their_range() {
+    let lines = analyze("loop:  load #1 // über\n  goto loop");
+
+    let ranges: Vec<(usize, usize, usize)> = lines
+        .iter()
+        .flatten()
+        .map(|token| {
+            let range = token.range();
+            assert_eq!(range.start().line(), range.end().line());
+            (
+                range.start().line(),
+                range.start().character(),
+                range.end().character(),
+            )
+        })
+        .collect();
+
+    // the label without `:`, the comment with `//` and the newline at the end of the first line
+    assert_eq!(
+        ranges,
+        vec![
+            (0, 0, 4),
+            (0, 7, 11),
+            (0, 12, 14),
+            (0, 15, 22),
+            (0, 22, 22),
+            (1, 2, 6),
+            (1, 7, 11)
+        ]
+    );
+}
+
+#[test]
+fn comments_after_end_are_no_errors() {
+    let lines = analyze("end // done");
+    assert_eq!(lines[0].len(), 2);
+    assert_eq!(lines[0][1].kind(), &LSPTokenKind::Comment);
+    assert!(diagnostics(&lines).is_empty());
+}
+
+#[test]
+fn diagnostics_include_label_errors() {
+    let lines = analyze("a: load 1\na: goto b\nstore #1\ngoto a\nend");
+
+    let diagnostics: Vec<(usize, usize, String)> = diagnostics(&lines)
+        .iter()
+        .map(|diagnostic| {
+            assert_eq!(diagnostic.severity(), DiagnosticSeverity::Error);
+            (
+                diagnostic.range().start().line(),
+                diagnostic.range().start().character(),
+                diagnostic.message(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            (1, 0, "Label 'a' is defined more than once".to_string()),
+            (1, 8, "Definition for label 'b' not found".to_string()),
+            (2, 6, "Argument '#1' is invalid or malformed".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn completions_depend_on_the_context() {
+    let lines = analyze(FACTORIAL_PROGRAM);
+
+    let at = |line, character| -> Vec<(String, CompletionKind)> {
+        completions(&lines, Position::new(line, character))
+            .iter()
+            .map(|completion| (completion.label().to_string(), completion.kind()))
+            .collect()
+    };
+
+    // instructions at the start of a line and after a label
+    let instructions = at(0, 4);
+    assert_eq!(instructions.len(), 10);
+    assert!(instructions.contains(&("jnzero".to_string(), CompletionKind::Keyword)));
+    assert_eq!(at(7, 10).len(), 10);
+
+    // labels after a jump
+    assert_eq!(
+        at(13, 11),
+        vec![
+            ("break".to_string(), CompletionKind::Label),
+            ("loop".to_string(), CompletionKind::Label),
+            ("return_one".to_string(), CompletionKind::Label),
+        ]
+    );
+
+    // nothing after an operand or in a comment
+    assert!(at(0, 11).is_empty());
+    assert!(at(1, 20).is_empty());
+}
+
+#[test]
+fn jump_arguments_lead_to_their_definition() {
+    let lines = analyze(FACTORIAL_PROGRAM);
+
+    let range = definition(&lines, Position::new(13, 12)).unwrap();
+    assert_eq!(range.start(), Position::new(7, 4));
+    assert_eq!(range.end(), Position::new(7, 8));
+    assert_eq!(
+        token_at(&lines, Position::new(13, 12)).unwrap().lexeme(),
+        "loop"
+    );
+
+    assert!(definition(&lines, Position::new(0, 0)).is_none());
+}
+
+#[test]
+fn semantic_tokens_are_relative_to_the_previous_token() {
+    let lines = analyze("loop: load #1\n  goto loop // again");
+
+    assert_eq!(
+        semantic_tokens(&lines),
+        vec![
+            0, 0, 4, 1, 1, // loop (function, declaration)
+            0, 6, 4, 0, 0, // load (keyword)
+            0, 5, 2, 2, 0, // #1 (number)
+            1, 2, 4, 0, 0, // goto (keyword)
+            0, 5, 4, 1, 0, // loop (function)
+            0, 5, 8, 4, 0, // // again (comment)
+        ]
+    );
+    assert_eq!(SEMANTIC_TOKEN_TYPES[1], "function");
+}
+
+#[test]
+fn hovering_a_label_shows_its_line_and_references() {
+    let lines = analyze(FACTORIAL_PROGRAM);
+
+    let loop_hover = hover(&lines, Position::new(13, 10), None).unwrap();
+    assert_eq!(loop_hover.range().start(), Position::new(13, 9));
+    assert!(
+        loop_hover
+            .contents()
+            .ends_with("Label `loop` on line 8, 1 reference")
+    );
+
+    let contents = hover(&lines, Position::new(14, 4), None).unwrap();
+    assert!(
+        contents
+            .contents()
+            .ends_with("Label `return_one` on line 15, 2 references")
+    );
+
+    let lines = analyze("goto missing");
+    let contents = hover(&lines, Position::new(0, 6), None).unwrap();
+    assert!(
+        contents
+            .contents()
+            .ends_with("Label `missing` is not defined")
+    );
+}
+
+#[test]
+fn hovering_a_register_shows_its_usage_and_value() {
+    let lines = analyze("load 1\nstore 2\nadd *1\nstore *2\nstore 2");
+
+    let contents = |line, character, machine| {
+        hover(&lines, Position::new(line, character), machine)
+            .unwrap()
+            .contents()
+            .to_string()
+    };
+
+    assert!(contents(1, 6, None).ends_with("`r2` is read 1 time and written 2 times"));
+    assert!(contents(0, 5, None).ends_with("`r1` is read 2 times and written 0 times"));
+
+    let machine = Machine::new_with_preseeded_registers(HashMap::from([(1, 7), (7, 42)]));
+    assert!(contents(0, 5, Some(&machine)).ends_with("Current value: `r1 = 7`"));
+    assert!(contents(2, 5, Some(&machine)).ends_with("Current value: `r1 = 7`, `r7 = 42`"));
+
+    // instructions only show their about text
+    assert_eq!(
+        contents(0, 0, Some(&machine)),
+        LSPTokenKind::Load.get_about_text()
+    );
+}
+
+#[test]
+fn unused_labels_and_a_missing_end_are_warnings() {
+    let lines = analyze("start: load 1\nunused: store 2\ngoto start");
+
+    let warnings: Vec<(usize, String)> = diagnostics(&lines)
+        .iter()
+        .map(|diagnostic| {
+            assert_eq!(diagnostic.severity(), DiagnosticSeverity::Warning);
+            (diagnostic.range().start().line(), diagnostic.message())
+        })
+        .collect();
+
+    assert_eq!(
+        warnings,
+        vec![
+            (1, "Label 'unused' is never used".to_string()),
+            (2, "The program has no 'end' instruction".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn invalid_directives_are_errors() {
+    let lines =
+        analyze("// @input r1 = x\n//@input 1=2,r3\n// @input r1 = 5\n// @inputs are fine\nend");
+
+    let errors: Vec<(usize, String)> = diagnostics(&lines)
+        .iter()
+        .map(|diagnostic| {
+            assert_eq!(diagnostic.severity(), DiagnosticSeverity::Error);
+            (diagnostic.range().start().line(), diagnostic.message())
+        })
+        .collect();
+
+    assert_eq!(
+        errors,
+        vec![
+            (
+                0,
+                "Directive '@input r1 = x' is malformed, expected e.g. '@input r1 = 5' or '@test name: r1 = 5 -> acc = 5'".to_string()
+            ),
+            (
+                1,
+                "Directive '@input 1=2,r3' is malformed, expected e.g. '@input r1 = 5' or '@test name: r1 = 5 -> acc = 5'".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn invalid_test_directives_are_errors() {
+    let source = "// @test five: r1 = 5 -> acc = 5\n// @test r1 = 5 -> acc = 5\n// @test zero: -> acc = 0; steps = 3\nload 1\nend";
+    assert!(arams_core::compile(source).is_err());
+
+    let lines: Vec<usize> = diagnostics(&analyze(source))
+        .iter()
+        .map(|diagnostic| {
+            assert!(matches!(diagnostic.error(), LSPError::InvalidDirective(_)));
+            diagnostic.range().start().line()
+        })
+        .collect();
+
+    assert_eq!(lines, vec![1, 2]);
+}
+
+// applies the edit of every action to the source code, lines are joined with `\n`
+fn fixed(source: &str, actions: &[CodeAction]) -> Vec<(String, String)> {
+    actions
+        .iter()
+        .map(|action| {
+            let mut document = arams_core::lsp::Document::new(source);
+            document.edit(action.edit());
+            (action.title().to_string(), document.text())
+        })
+        .collect()
+}
+
+#[test]
+fn quick_fixes_are_text_edits() {
+    let all_lines = |source| {
+        code_actions(
+            &analyze(source),
+            Range::new(Position::new(0, 0), Position::new(100, 0)),
+        )
+    };
+
+    let source = "lod 1\nstor #2\njzro done\nunused:\nend";
+    assert_eq!(
+        fixed(source, &all_lines(source)),
+        vec![
+            (
+                "Change to 'load'".to_string(),
+                "load 1\nstor #2\njzro done\nunused:\nend".to_string()
+            ),
+            (
+                "Change to 'store'".to_string(),
+                "lod 1\nstore #2\njzro done\nunused:\nend".to_string()
+            ),
+            (
+                "Change to 'jzero'".to_string(),
+                "lod 1\nstor #2\njzero done\nunused:\nend".to_string()
+            ),
+            (
+                "Remove unused label 'unused'".to_string(),
+                "lod 1\nstor #2\njzro done\nend".to_string()
+            ),
+        ]
+    );
+
+    let source = "loop: store #2\nunused: goto missing // typo\ngoto loop\n\n";
+    assert_eq!(
+        fixed(source, &all_lines(source)),
+        vec![
+            (
+                "Store to register 2".to_string(),
+                "loop: store 2\nunused: goto missing // typo\ngoto loop\n".to_string()
+            ),
+            (
+                "Remove unused label 'unused'".to_string(),
+                "loop: store #2\ngoto missing // typo\ngoto loop\n".to_string()
+            ),
+            (
+                "Create label 'missing'".to_string(),
+                "loop: store #2\nunused: goto missing // typo\ngoto loop\nmissing:\n".to_string()
+            ),
+            (
+                "Append 'end'".to_string(),
+                "loop: store #2\nunused: goto missing // typo\ngoto loop\nend\n".to_string()
+            ),
+        ]
+    );
+
+    // only the diagnostics on the lines of the range are fixed
+    let actions = code_actions(
+        &analyze(source),
+        Range::new(Position::new(1, 3), Position::new(1, 3)),
+    );
+    assert_eq!(actions.len(), 2);
+    assert!(matches!(
+        actions[0].diagnostic().error(),
+        LSPError::UnusedLabel(_)
+    ));
+
+    // missing labels are created once, on a new line before the last `end`
+    let source = "jzero missing\nend\n    goto missing\n    end";
+    assert_eq!(
+        fixed(source, &all_lines(source)),
+        vec![(
+            "Create label 'missing'".to_string(),
+            "jzero missing\nend\n    goto missing\n    missing:\n    end".to_string()
+        )]
+    );
+
+    // words that are too different from every instruction are not changed
+    assert!(all_lines("foo 1\nend").is_empty());
+}
+
+#[test]
+fn inlay_hints_show_jump_targets_and_indirect_addresses() {
+    let lines = analyze("load *1\nloop: sub #1\njnzero loop\ngoto missing\nstore *x\nend");
+
+    let hints: Vec<(Position, String, String)> = inlay_hints(&lines, None)
+        .iter()
+        .map(|hint| {
+            (
+                hint.position(),
+                hint.label().to_string(),
+                hint.tooltip().to_string(),
+            )
+        })
+        .collect();
+
+    // unknown labels and invalid operands get no hints
+    assert_eq!(
+        hints,
+        vec![
+            (
+                Position::new(0, 7),
+                "value of r[1] used as address".to_string(),
+                "value of r[1] used as address".to_string()
+            ),
+            (
+                Position::new(2, 11),
+                "line 2".to_string(),
+                "Jumps to instruction 1 on line 2".to_string()
+            ),
+        ]
+    );
+
+    // while debugging the resolved address is shown
+    let machine = Machine::new_with_preseeded_registers(HashMap::from([(1, 7)]));
+    assert_eq!(
+        inlay_hints(&lines, Some(&machine))[0].label(),
+        "r[1] = 7 → r[7]"
+    );
+}
+
+const SECTIONED_PROGRAM: &str = "// computes r1 * r2
+// the result is in the accumulator
+start: load 1
+    jzero done
+loop: load 3
+    add 2
+    store 3 // sum
+
+    // one less to go
+    // until r1 is zero
+done: load 3
+end";
+
+#[test]
+fn labels_are_document_symbols() {
+    let lines = analyze(SECTIONED_PROGRAM);
+
+    let symbols: Vec<(String, String, Range)> = document_symbols(&lines)
+        .iter()
+        .map(|symbol| {
+            assert_eq!(symbol.selection_range().start(), symbol.range().start());
+            (
+                symbol.name().to_string(),
+                symbol.detail().to_string(),
+                symbol.range(),
+            )
+        })
+        .collect();
+
+    let range = |start, end: (usize, usize)| {
+        Range::new(Position::new(start, 0), Position::new(end.0, end.1))
+    };
+    assert_eq!(
+        symbols,
+        vec![
+            (
+                "start".to_string(),
+                "2 instructions".to_string(),
+                range(2, (3, 14))
+            ),
+            (
+                "loop".to_string(),
+                "3 instructions".to_string(),
+                range(4, (6, 18))
+            ),
+            (
+                "done".to_string(),
+                "2 instructions".to_string(),
+                range(10, (11, 3))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn label_sections_and_comments_can_be_folded() {
+    let lines = analyze(SECTIONED_PROGRAM);
+
+    let ranges: Vec<(usize, usize, FoldingRangeKind)> = folding_ranges(&lines)
+        .iter()
+        .map(|range| (range.start_line(), range.end_line(), range.kind()))
+        .collect();
+
+    assert_eq!(
+        ranges,
+        vec![
+            (0, 1, FoldingRangeKind::Comment),
+            (2, 3, FoldingRangeKind::Region),
+            (4, 6, FoldingRangeKind::Region),
+            (8, 9, FoldingRangeKind::Comment),
+            (10, 11, FoldingRangeKind::Region),
+        ]
+    );
+}
@@ -0,0 +1,91 @@
+use arams_core::{ArithmeticMode, Machine, RuntimeError, compile, resume};
+use std::collections::HashMap;
+
+fn run_with(
+    source_code: &str,
+    registers: HashMap<usize, u64>,
+    arithmetic_mode: ArithmeticMode,
+    strict_reads: bool,
+) -> Result<Machine, RuntimeError> {
+    let program = compile(source_code).unwrap();
+    let mut machine = Machine::new_with_preseeded_registers(registers);
+    machine.set_arithmetic_mode(arithmetic_mode);
+    machine.set_strict_reads(strict_reads);
+    resume(&program, machine)
+}
+
+#[test]
+fn wrapping_arithmetic_is_the_default() {
+    let machine = run_with(
+        "load #0\nsub #1\nend",
+        HashMap::new(),
+        ArithmeticMode::default(),
+        false,
+    )
+    .unwrap();
+    assert_eq!(machine.get_accumulator(), u64::MAX);
+}
+
+#[test]
+fn saturating_arithmetic_clamps_results() {
+    let machine = run_with(
+        "load #3\nsub #5\nstore 1\nload #18446744073709551615\nadd #1\nend",
+        HashMap::new(),
+        ArithmeticMode::Saturating,
+        false,
+    )
+    .unwrap();
+    assert_eq!(machine.get_register_value(1), &0);
+    assert_eq!(machine.get_accumulator(), u64::MAX);
+}
+
+#[test]
+fn checked_arithmetic_reports_overflows_with_their_line() {
+    let err = run_with(
+        "load #2\nmul #9223372036854775808\nend",
+        HashMap::new(),
+        ArithmeticMode::Checked,
+        false,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        RuntimeError::ArithmeticOverflow { line: Some(2) }
+    ));
+
+    let machine = run_with(
+        "load #2\nmul #3\nend",
+        HashMap::new(),
+        ArithmeticMode::Checked,
+        false,
+    )
+    .unwrap();
+    assert_eq!(machine.get_accumulator(), 6);
+}
+
+#[test]
+fn strict_reads_reject_uninitialised_registers() {
+    let err = run_with(
+        "load 1\nadd 2\nend",
+        HashMap::from([(1, 5)]),
+        ArithmeticMode::default(),
+        true,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        RuntimeError::UninitializedRegister {
+            register: 2,
+            line: Some(2)
+        }
+    ));
+
+    let machine = run_with(
+        "load #4\nstore 2\nload 1\nadd 2\nend",
+        HashMap::from([(1, 5)]),
+        ArithmeticMode::default(),
+        true,
+    )
+    .unwrap();
+    assert_eq!(machine.get_accumulator(), 9);
+}
@@ -0,0 +1,147 @@
+use arams_core::{Optimization, compile, disassemble, execute, optimize};
+use std::collections::HashMap;
+
+const FACTORIAL_PROGRAM: &str = "load 1
+    jzero return_one
+    sub #1
+    jzero return_one
+    load 1
+    store 2
+    loop: load 1
+    sub #1
+    jzero break
+    store 1
+    mul 2
+    store 2
+    goto loop
+    return_one: load #1
+    end
+    break: load 2
+    end";
+
+#[test]
+fn factorial_program_is_already_optimal() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let (optimized, report) = optimize(&program);
+    assert!(report.is_empty());
+    assert_eq!(optimized, program);
+}
+
+#[test]
+fn redundant_instructions_are_removed() {
+    let program = compile(
+        "load 1
+        add #0
+        store 2
+        load 2
+        mul #1
+        end
+        load 3",
+    )
+    .unwrap();
+    let (optimized, report) = optimize(&program);
+
+    assert_eq!(disassemble(&optimized), "load 1\nstore 2\nend\n");
+    assert_eq!(report.instructions_before(), 7);
+    assert_eq!(report.instructions_after(), 3);
+    assert_eq!(
+        report.changes(),
+        &[
+            (Some(4), Optimization::RedundantLoadRemoved { address: 2 }),
+            (
+                Some(2),
+                Optimization::NoOpRemoved {
+                    instruction: compile("add #0").unwrap().instructions()[0].clone()
+                }
+            ),
+            (
+                Some(5),
+                Optimization::NoOpRemoved {
+                    instruction: compile("mul #1").unwrap().instructions()[0].clone()
+                }
+            ),
+            (
+                Some(7),
+                Optimization::UnreachableRemoved {
+                    instruction: compile("load 3").unwrap().instructions()[0].clone()
+                }
+            ),
+        ]
+    );
+    assert_eq!(optimized.source_lines(), &[1, 3, 6]);
+}
+
+#[test]
+fn loads_that_are_jumped_to_are_kept() {
+    let program = compile("store 1\nloop: load 1\nsub #1\njnzero loop").unwrap();
+    let (optimized, report) = optimize(&program);
+    assert!(report.is_empty());
+    assert_eq!(optimized, program);
+}
+
+#[test]
+fn jump_chains_are_threaded() {
+    let program = compile(
+        "load 1
+        jzero first
+        goto second
+        first: goto second
+        second: goto third
+        load #5
+        third: end",
+    )
+    .unwrap();
+    let (optimized, report) = optimize(&program);
+
+    assert_eq!(
+        disassemble(&optimized),
+        "       load 1\n       jzero third\n       goto third\nthird: end\n"
+    );
+    assert!(report.changes().contains(&(
+        Some(2),
+        Optimization::JumpThreaded {
+            from: "first".to_string(),
+            to: "third".to_string()
+        }
+    )));
+    assert!(report.changes().iter().any(|(_, change)| change
+        == &Optimization::UnusedLabelRemoved {
+            label: "first".to_string()
+        }));
+}
+
+#[test]
+fn infinite_goto_cycles_are_kept() {
+    let program = compile("a: goto b\nb: goto a").unwrap();
+    let (optimized, _) = optimize(&program);
+    assert_eq!(optimized, program);
+}
+
+#[test]
+fn optimized_programs_compute_the_same_results() {
+    let program = compile(
+        "load 1
+        store 3
+        load 3
+        loop: jzero done
+        add #0
+        sub #1
+        store 3
+        load 3
+        goto skip
+        skip: goto loop
+        done: load 3
+        end",
+    )
+    .unwrap();
+    let (optimized, report) = optimize(&program);
+    assert!(report.instructions_after() < report.instructions_before());
+
+    for n in 0..5 {
+        let registers = HashMap::from([(1, n)]);
+        let expected = execute(program.clone(), Some(registers.clone())).unwrap();
+        let machine = execute(optimized.clone(), Some(registers)).unwrap();
+        assert_eq!(machine.get_accumulator(), expected.get_accumulator());
+        assert_eq!(machine.get_registers(), expected.get_registers());
+    }
+}
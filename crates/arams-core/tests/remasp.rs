@@ -0,0 +1,69 @@
+use arams_core::{compile, execute, remasp};
+use std::collections::HashMap;
+
+const FACTORIAL_PROGRAM: &str = "load 1
+    jzero return_one
+    sub #1
+    jzero return_one
+    load 1
+    store 2
+    loop: load 1
+    sub #1
+    jzero break
+    store 1
+    mul 2
+    store 2
+    goto loop
+    return_one: load #1
+    end
+    break: load 2
+    end";
+
+const FACTORIAL_REMASP: &str = include_str!("golden/factorial.remasp");
+const FACTORIAL_REMASP_HANDWRITTEN: &str = include_str!("golden/factorial-handwritten.remasp");
+
+#[test]
+fn factorial_program_exports_to_golden_file() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let registers = HashMap::from([(1, 5)]);
+    assert_eq!(remasp::export(&program, &registers), FACTORIAL_REMASP);
+}
+
+#[test]
+fn golden_files_import_to_factorial_program() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+
+    for source in [FACTORIAL_REMASP, FACTORIAL_REMASP_HANDWRITTEN] {
+        let (imported, registers) = remasp::import(source).unwrap();
+        assert_eq!(imported, program);
+        assert_eq!(registers, HashMap::from([(1, 5)]));
+
+        let machine = execute(imported, Some(registers)).unwrap();
+        assert_eq!(machine.get_accumulator(), 120);
+    }
+}
+
+#[test]
+fn golden_files_round_trip() {
+    for source in [FACTORIAL_REMASP, FACTORIAL_REMASP_HANDWRITTEN] {
+        let (program, registers) = remasp::import(source).unwrap();
+        let exported = remasp::export(&program, &registers);
+        assert_eq!(exported, FACTORIAL_REMASP);
+        assert_eq!(remasp::import(exported).unwrap(), (program, registers));
+    }
+}
+
+#[test]
+fn labels_without_instruction_are_kept() {
+    let program = compile("start:\nloop: load 1\ngoto loop\nexit:").unwrap();
+    let exported = remasp::export(&program, &HashMap::new());
+    assert_eq!(exported, "loop:\nstart: LOAD 1\n       GOTO loop\nexit:\n");
+    assert_eq!(remasp::import(exported).unwrap().0, program);
+}
+
+#[test]
+fn malformed_register_initialisation_is_rejected() {
+    let errors = remasp::import("// R1 = five\nload 1\nend").unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line_number(), 1);
+}
@@ -0,0 +1,80 @@
+use arams_core::{Machine, Snapshot, StopReason, compile, execute};
+use std::collections::HashMap;
+
+const FACTORIAL_PROGRAM: &str = "load 1
+    jzero return_one
+    sub #1
+    jzero return_one
+    load 1
+    store 2
+    loop: load 1
+    sub #1
+    jzero break
+    store 1
+    mul 2
+    store 2
+    goto loop
+    return_one: load #1
+    end
+    break: load 2
+    end";
+
+fn paused_factorial_snapshot(steps: usize) -> Snapshot {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let machine = Machine::new_with_preseeded_registers(HashMap::from([(1, 5)]));
+    let mut snapshot = Snapshot::new(program, machine);
+    for _ in 0..steps {
+        snapshot.step().unwrap();
+    }
+    snapshot
+}
+
+#[test]
+fn paused_execution_resumes_with_the_same_result() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let expected = execute(program, Some(HashMap::from([(1, 5)]))).unwrap();
+
+    let snapshot = paused_factorial_snapshot(20);
+    assert!(!snapshot.machine().is_stopped());
+    assert_eq!(snapshot.machine().get_program_counter(), 6);
+
+    let machine = snapshot.resume().unwrap();
+    assert_eq!(machine, expected);
+    assert_eq!(machine.get_accumulator(), 120);
+}
+
+#[test]
+fn stepping_a_stopped_machine_fails() {
+    let mut snapshot = Snapshot::new(compile("end").unwrap(), Machine::new());
+    snapshot.step().unwrap();
+    assert!(snapshot.machine().is_stopped());
+    assert!(snapshot.step().is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn paused_execution_survives_json_round_trip() {
+    let snapshot = paused_factorial_snapshot(20);
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let restored: Snapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, snapshot);
+    assert_eq!(restored.resume().unwrap().get_accumulator(), 120);
+}
+
+#[test]
+fn machine_counts_steps_and_records_why_it_stopped() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let machine = execute(program, Some(HashMap::from([(1, 3)]))).unwrap();
+    assert_eq!(machine.get_stop_reason(), Some(StopReason::End));
+    assert_eq!(machine.get_steps(), 25);
+
+    let machine = execute(compile("load #1").unwrap(), None).unwrap();
+    assert_eq!(machine.get_stop_reason(), Some(StopReason::EndOfProgram));
+    assert_eq!(machine.get_steps(), 1);
+
+    let machine = execute(compile("load #4\ndiv #0\nload #1").unwrap(), None).unwrap();
+    assert_eq!(machine.get_stop_reason(), Some(StopReason::DivisionByZero));
+    assert_eq!(machine.get_accumulator(), 4);
+    assert_eq!(machine.get_steps(), 2);
+}
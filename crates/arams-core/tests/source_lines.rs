@@ -0,0 +1,56 @@
+use arams_core::{Machine, RuntimeError, Snapshot, bytecode, compile, execute};
+
+const PROGRAM: &str = "// comment
+    load #2
+
+    loop: sub #1
+    jnzero loop
+    goto missing
+    end";
+
+#[test]
+fn instructions_map_to_source_lines() {
+    let program = compile(PROGRAM).unwrap();
+    assert_eq!(program.source_lines(), &[2, 4, 5, 6, 7]);
+    assert_eq!(program.source_line(3), Some(6));
+    assert_eq!(program.instruction_at_line(4), Some(1));
+    assert_eq!(program.instruction_at_line(3), None);
+    assert_eq!(program.label_at(0), None);
+    assert_eq!(program.label_at(2), Some("loop"));
+}
+
+#[test]
+fn unknown_label_error_points_at_line() {
+    let program = compile(PROGRAM).unwrap();
+    let err = execute(program, None).unwrap_err();
+    assert!(matches!(
+        &err,
+        RuntimeError::UnknownLabel { label, line: Some(6) } if label == "missing"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Tried to jump to unknown label 'missing' at line 6"
+    );
+}
+
+#[test]
+fn machine_stopped_error_points_at_line() {
+    let mut snapshot = Snapshot::new(compile("load 1\nend").unwrap(), Machine::new());
+    snapshot.step().unwrap();
+    snapshot.step().unwrap();
+    assert!(matches!(
+        snapshot.step(),
+        Err(RuntimeError::MachineStopped { line: Some(2) })
+    ));
+}
+
+#[test]
+fn source_lines_survive_bytecode_round_trip() {
+    let program = compile(PROGRAM.replace("missing", "loop")).unwrap();
+
+    let decoded = bytecode::decode(&bytecode::encode(&program, true).unwrap()).unwrap();
+    assert_eq!(decoded.source_lines(), program.source_lines());
+
+    let stripped = bytecode::decode(&bytecode::encode(&program, false).unwrap()).unwrap();
+    assert!(stripped.source_lines().is_empty());
+}
@@ -0,0 +1,105 @@
+use arams_core::{CompileErrorKind, TestCase, TestOutcome, compile, disassemble, run_test};
+use std::collections::HashMap;
+
+const FACTORIAL_PROGRAM: &str = "// @test factorial of 5: r1 = 5 -> acc = 120, r2 = 120
+    // @test factorial of 0: r1 = 0 -> acc = 1; max_steps = 10
+    // @test wrong: r1 = 3 -> acc = 7, r2 = 6, r3 = 1
+    load 1
+    jzero return_one
+    sub #1
+    jzero return_one
+    load 1
+    store 2
+    loop: load 1
+    sub #1
+    jzero break
+    store 1
+    mul 2
+    store 2
+    goto loop
+    return_one: load #1
+    end
+    break: load 2
+    end";
+
+#[test]
+fn test_directives_are_stored_on_the_program() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let test_cases = program.test_cases();
+    assert_eq!(test_cases.len(), 3);
+
+    assert_eq!(test_cases[0].name(), "factorial of 5");
+    assert_eq!(test_cases[0].inputs(), &HashMap::from([(1, 5)]));
+    assert_eq!(test_cases[0].expected_accumulator(), Some(120));
+    assert_eq!(
+        test_cases[0].expected_registers(),
+        &HashMap::from([(2, 120)])
+    );
+    assert_eq!(test_cases[0].max_steps(), None);
+    assert_eq!(test_cases[1].max_steps(), Some(10));
+}
+
+#[test]
+fn test_cases_report_mismatches() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let outcomes: Vec<TestOutcome> = program
+        .test_cases()
+        .iter()
+        .map(|test_case| run_test(&program, test_case, None))
+        .collect();
+
+    assert!(outcomes[0].is_passed());
+    assert!(outcomes[1].is_passed());
+
+    let TestOutcome::Failed { mismatches, .. } = &outcomes[2] else {
+        panic!("expected a failed test, got {:?}", outcomes[2]);
+    };
+    let mismatches: Vec<String> = mismatches.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        mismatches,
+        ["acc: expected 7, got 6", "r3: expected 1, got 0"]
+    );
+}
+
+#[test]
+fn step_bound_stops_endless_programs() {
+    let program = compile("loop: goto loop").unwrap();
+    let test_case = TestCase::new(
+        "endless".to_string(),
+        HashMap::new(),
+        Some(0),
+        HashMap::new(),
+        None,
+    );
+
+    assert!(matches!(
+        run_test(&program, &test_case, Some(50)),
+        TestOutcome::StepLimitExceeded { max_steps: 50 }
+    ));
+}
+
+#[test]
+fn invalid_test_directives_are_compile_errors() {
+    for directive in [
+        "// @test r1 = 5 -> acc = 1",
+        "// @test name: r1 = 5",
+        "// @test name: -> acc = x",
+        "// @test name: -> acc = 1; steps = 5",
+    ] {
+        let errors = compile(format!("{}\nend", directive)).unwrap_err();
+        assert!(matches!(
+            errors[0].kind(),
+            CompileErrorKind::InvalidDirective
+        ));
+    }
+}
+
+#[test]
+fn test_cases_survive_disassembly() {
+    let program = compile(FACTORIAL_PROGRAM).unwrap();
+    let source = disassemble(&program);
+    assert!(source.starts_with(
+        "// @test factorial of 5: r1 = 5 -> acc = 120, r2 = 120\n// @test factorial of 0: r1 = 0 -> acc = 1; max_steps = 10\n"
+    ));
+    assert_eq!(compile(source).unwrap().test_cases(), program.test_cases());
+}
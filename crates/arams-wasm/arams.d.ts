@@ -6,33 +6,264 @@ export { default } from "./arams_wasm";
 
 export type SourceCode = string | string[];
 
-export type Machine = {
-    accumulator: number;
-    registers: Map<number, number>;
-};
-
+// @generated begin
 export type CompilationError = {
     line: number;
     message: string;
 };
 
-export type ExecutionError = {
+export type CompilationErrors = {
+    errors: CompilationError[];
+};
+
+export type RuntimeError = {
+    line: number | null;
     message: string;
 };
 
+export type StopReason = "end" | "end_of_program" | "division_by_zero" | "step_limit";
+
+export type TraceEntry = {
+    step: number;
+    program_counter: number;
+    line: number | null;
+    instruction: string;
+    accumulator: number;
+};
+
+export type Machine = {
+    registers: Map<number, number>;
+    accumulator: number;
+    steps: number;
+    stop_reason: StopReason | null;
+    trace: TraceEntry[] | null;
+};
+
+export type RunResult =
+    | ({ status: "machine" } & Machine)
+    | ({ status: "compilation_error" } & CompilationErrors)
+    | ({ status: "execution_error" } & RuntimeError);
+
+export type ArithmeticMode = "wrapping" | "saturating" | "checked";
+
+export type Dialect = "arams" | "remasp";
+
+export type RunOptions = {
+    max_steps?: number | null;
+    arithmetic?: ArithmeticMode;
+    dialect?: Dialect;
+    trace?: boolean;
+    strict_reads?: boolean;
+};
+
+export type LSPTokenKind =
+    | "load"
+    | "store"
+    | "add"
+    | "sub"
+    | "mul"
+    | "div"
+    | "goto"
+    | "jzero"
+    | "jnzero"
+    | "end"
+    | "label_definition"
+    | "jump_argument"
+    | "immediate_argument"
+    | "indirect_address_argument"
+    | "direct_address_argument"
+    | "comment"
+    | "newline"
+    | "unknown";
+
 export type AnalyzeToken = {
-    kind: string;
+    kind: LSPTokenKind;
     lexeme: string;
     errors: string[];
     about: string;
 };
 
+export type SessionStatus = "running" | "stopped" | "error";
+
+export type SessionState = {
+    status: SessionStatus;
+    program_counter: number;
+    line: number | null;
+    accumulator: number;
+    registers: Map<number, number>;
+    steps: number;
+    stop_reason: StopReason | null;
+    error: RuntimeError | null;
+    can_step_back: boolean;
+};
+
+export type ChunkStatus = "paused" | "running" | "finished";
+
+export type ChunkResult = {
+    status: ChunkStatus;
+    state: SessionState;
+};
+
+export type Position = {
+    line: number;
+    character: number;
+};
+
+export type Range = {
+    start: Position;
+    end: Position;
+};
+
+export type DiagnosticSeverity = "error" | "warning";
+
+export type Diagnostic = {
+    range: Range;
+    severity: DiagnosticSeverity;
+    message: string;
+};
+
+export type CompletionKind = "keyword" | "label";
+
+export type CompletionItem = {
+    label: string;
+    kind: CompletionKind;
+    detail: string;
+    documentation: string;
+};
+
+export type TextEdit = {
+    range: Range;
+    text: string;
+};
+
+export type CodeAction = {
+    title: string;
+    edit: TextEdit;
+    diagnostic: Diagnostic;
+};
+
+export type Hover = {
+    range: Range;
+    contents: string;
+};
+
+export type InlayHint = {
+    position: Position;
+    label: string;
+    tooltip: string;
+};
+
+export type DocumentSymbol = {
+    name: string;
+    detail: string;
+    range: Range;
+    selection_range: Range;
+};
+
+export type FoldingRangeKind = "region" | "comment";
+
+export type FoldingRange = {
+    start_line: number;
+    end_line: number;
+    kind: FoldingRangeKind;
+};
+
+export type SemanticTokensLegend = {
+    token_types: string[];
+    token_modifiers: string[];
+};
+// @generated end
+
+// the name used before `RuntimeError` was generated
+export type ExecutionError = RuntimeError;
+
+export declare class Session {
+    /**
+     * Compiles a program and prepares its execution.
+     * @throws CompilationError[] if the program does not compile
+     */
+    constructor(input: SourceCode, registers?: Map<number, number>);
+    free(): void;
+    /** Executes the next instruction. */
+    step(): SessionState;
+    /** Restores the state before the last step, the last 10000 steps can be undone. */
+    stepBack(): SessionState;
+    /** Executes instructions until a breakpoint is reached, the program stops or 100000 steps have been executed. */
+    continue(): SessionState;
+    /** Executes at most `maxSteps` instructions, stops early at breakpoints. `stepBack()` undoes the whole chunk. */
+    runSteps(maxSteps: number): ChunkResult;
+    /** Replaces all breakpoints, given as source line numbers. */
+    setBreakpoints(lines: number[]): void;
+    getState(): SessionState;
+    /** Like `hover()`, with the current values of the hovered registers. */
+    hover(input: SourceCode, line: number, character: number): Hover | null;
+    /** Like `inlayHints()`, with the addresses indirect operands currently resolve to. */
+    inlayHints(input: SourceCode): InlayHint[];
+    /** Restarts the program with the initial registers, breakpoints are kept. */
+    reset(): SessionState;
+}
+
 export declare function run(
     input: SourceCode,
     registers: Map<number, number>,
-):
-    | { status: "ok"; machine: Machine }
-    | { status: "compilationerror"; compilation_error: CompilationError[] }
-    | { status: "executionerror"; execution_error: ExecutionError };
+    options?: RunOptions,
+): RunResult;
+
+export declare function resume(
+    snapshot: string,
+): Exclude<RunResult, { status: "compilation_error" }>;
 
 export declare function analyze(input: SourceCode): AnalyzeToken[][];
+
+/** Token errors, unknown and duplicate labels, warnings for unused labels and a missing `end`. */
+export declare function diagnostics(input: SourceCode): Diagnostic[];
+
+/**
+ * Instructions at the start of a line, labels after a jump.
+ * Positions are zero-based, characters are counted in UTF-16 code units.
+ */
+export declare function completions(
+    input: SourceCode,
+    line: number,
+    character: number,
+): CompletionItem[];
+
+/** The label definition of the jump argument or label at the position. */
+export declare function definition(
+    input: SourceCode,
+    line: number,
+    character: number,
+): Range | null;
+
+/**
+ * Markdown about the token at the position: what an instruction does, where a label is defined
+ * and how often it is used, how often a register is read and written.
+ */
+export declare function hover(input: SourceCode, line: number, character: number): Hover | null;
+
+/**
+ * The line and instruction index jump arguments go to, how indirect operands `*n` are resolved.
+ * Each hint belongs directly behind the argument at its `position`.
+ */
+export declare function inlayHints(input: SourceCode): InlayHint[];
+
+/**
+ * Quick fixes for the diagnostics on the lines of the range: creating a missing label, storing to
+ * a register instead of an immediate, fixing misspelled instructions, appending `end` and removing
+ * unused labels. A missing label gets one action, it is defined on a new line before the last `end`.
+ */
+export declare function codeActions(input: SourceCode, range: Range): CodeAction[];
+
+/** One symbol per label, its `range` covers the instructions up to the next label. */
+export declare function documentSymbols(input: SourceCode): DocumentSymbol[];
+
+/** Label sections and runs of comment lines, the end line is included. */
+export declare function foldingRanges(input: SourceCode): FoldingRange[];
+
+/** Formats the source code like `arams fmt`. */
+export declare function format(input: SourceCode): string;
+
+/** Five integers per token, encoded like LSP semantic tokens, see `semanticTokensLegend()`. */
+export declare function semanticTokens(input: SourceCode): Uint32Array;
+
+export declare function semanticTokensLegend(): SemanticTokensLegend;
@@ -1,13 +1,16 @@
-use arams_core::IntoSourceCode;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
-#[derive(Serialize, Deserialize)]
-pub struct AnalyzeToken {
-    pub kind: String,
-    pub lexeme: String,
-    pub errors: Vec<String>,
-    pub about: String,
+use crate::{conversions::source_code_from_js, typings::typescript};
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct AnalyzeToken {
+        pub kind: String as "LSPTokenKind",
+        pub lexeme: String,
+        pub errors: Vec<String>,
+        pub about: String,
+    }
 }
 
 impl From<arams_core::lsp::LSPToken> for AnalyzeToken {
@@ -23,15 +26,7 @@ impl From<arams_core::lsp::LSPToken> for AnalyzeToken {
 
 #[wasm_bindgen]
 pub fn analyze(input: JsValue) -> Result<JsValue, JsError> {
-    let source_code = if input.is_string() {
-        serde_wasm_bindgen::from_value::<String>(input)?.into_lines()
-    } else if input.is_array() {
-        serde_wasm_bindgen::from_value(input)?
-    } else {
-        return Err(JsError::new(
-            "Input must be a string or an array of strings",
-        ));
-    };
+    let source_code = source_code_from_js(input)?;
 
     let lines = arams_core::lsp::analyze(source_code);
 
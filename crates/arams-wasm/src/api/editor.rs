@@ -0,0 +1,142 @@
+use arams_core::lsp;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    conversions::source_code_from_js,
+    models::{
+        CodeAction, CompletionItem, Diagnostic, DocumentSymbol, FoldingRange, Hover, InlayHint,
+        Range, SemanticTokensLegend,
+    },
+};
+
+#[wasm_bindgen]
+pub fn diagnostics(input: JsValue) -> Result<JsValue, JsError> {
+    let lines = lsp::analyze(source_code_from_js(input)?);
+
+    Ok(serde_wasm_bindgen::to_value(
+        &lsp::diagnostics(&lines)
+            .iter()
+            .map(Diagnostic::from)
+            .collect::<Vec<_>>(),
+    )?)
+}
+
+// positions are zero-based, characters are counted in UTF-16 code units
+#[wasm_bindgen]
+pub fn completions(input: JsValue, line: usize, character: usize) -> Result<JsValue, JsError> {
+    let lines = lsp::analyze(source_code_from_js(input)?);
+
+    Ok(serde_wasm_bindgen::to_value(
+        &lsp::completions(&lines, lsp::Position::new(line, character))
+            .iter()
+            .map(CompletionItem::from)
+            .collect::<Vec<_>>(),
+    )?)
+}
+
+// the range of the label definition, `null` if there is no label at the position
+#[wasm_bindgen]
+pub fn definition(input: JsValue, line: usize, character: usize) -> Result<JsValue, JsError> {
+    let lines = lsp::analyze(source_code_from_js(input)?);
+
+    Ok(serde_wasm_bindgen::to_value(
+        &lsp::definition(&lines, lsp::Position::new(line, character)).map(Range::from),
+    )?)
+}
+
+// markdown about the token at the position, `Session.hover` adds the current register values
+#[wasm_bindgen]
+pub fn hover(input: JsValue, line: usize, character: usize) -> Result<JsValue, JsError> {
+    hover_with_machine(input, line, character, None)
+}
+
+pub(crate) fn hover_with_machine(
+    input: JsValue,
+    line: usize,
+    character: usize,
+    machine: Option<&arams_core::Machine>,
+) -> Result<JsValue, JsError> {
+    let lines = lsp::analyze(source_code_from_js(input)?);
+
+    Ok(serde_wasm_bindgen::to_value(
+        &lsp::hover(&lines, lsp::Position::new(line, character), machine).map(Hover::from),
+    )?)
+}
+
+// `Session.inlayHints` adds the resolved addresses of indirect operands
+#[wasm_bindgen(js_name = inlayHints)]
+pub fn inlay_hints(input: JsValue) -> Result<JsValue, JsError> {
+    inlay_hints_with_machine(input, None)
+}
+
+pub(crate) fn inlay_hints_with_machine(
+    input: JsValue,
+    machine: Option<&arams_core::Machine>,
+) -> Result<JsValue, JsError> {
+    let lines = lsp::analyze(source_code_from_js(input)?);
+
+    Ok(serde_wasm_bindgen::to_value(
+        &lsp::inlay_hints(&lines, machine)
+            .iter()
+            .map(InlayHint::from)
+            .collect::<Vec<_>>(),
+    )?)
+}
+
+// quick fixes for the diagnostics on the lines of the range
+#[wasm_bindgen(js_name = codeActions)]
+pub fn code_actions(input: JsValue, range: JsValue) -> Result<JsValue, JsError> {
+    let lines = lsp::analyze(source_code_from_js(input)?);
+    let range: Range = serde_wasm_bindgen::from_value(range)?;
+
+    Ok(serde_wasm_bindgen::to_value(
+        &lsp::code_actions(&lines, range.into())
+            .iter()
+            .map(CodeAction::from)
+            .collect::<Vec<_>>(),
+    )?)
+}
+
+// one symbol per label, for outline views
+#[wasm_bindgen(js_name = documentSymbols)]
+pub fn document_symbols(input: JsValue) -> Result<JsValue, JsError> {
+    let lines = lsp::analyze(source_code_from_js(input)?);
+
+    Ok(serde_wasm_bindgen::to_value(
+        &lsp::document_symbols(&lines)
+            .iter()
+            .map(DocumentSymbol::from)
+            .collect::<Vec<_>>(),
+    )?)
+}
+
+#[wasm_bindgen(js_name = foldingRanges)]
+pub fn folding_ranges(input: JsValue) -> Result<JsValue, JsError> {
+    let lines = lsp::analyze(source_code_from_js(input)?);
+
+    Ok(serde_wasm_bindgen::to_value(
+        &lsp::folding_ranges(&lines)
+            .iter()
+            .map(FoldingRange::from)
+            .collect::<Vec<_>>(),
+    )?)
+}
+
+#[wasm_bindgen]
+pub fn format(input: JsValue) -> Result<String, JsError> {
+    Ok(lsp::format(source_code_from_js(input)?))
+}
+
+#[wasm_bindgen(js_name = semanticTokens)]
+pub fn semantic_tokens(input: JsValue) -> Result<Vec<u32>, JsError> {
+    let lines = lsp::analyze(source_code_from_js(input)?);
+    Ok(lsp::semantic_tokens(&lines))
+}
+
+#[wasm_bindgen(js_name = semanticTokensLegend)]
+pub fn semantic_tokens_legend() -> Result<JsValue, JsError> {
+    Ok(serde_wasm_bindgen::to_value(&SemanticTokensLegend {
+        token_types: lsp::SEMANTIC_TOKEN_TYPES.map(String::from).to_vec(),
+        token_modifiers: lsp::SEMANTIC_TOKEN_MODIFIERS.map(String::from).to_vec(),
+    })?)
+}
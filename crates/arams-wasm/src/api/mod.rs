@@ -1,5 +1,9 @@
 pub mod analyze;
+pub mod editor;
 pub mod run;
+pub mod session;
 
 pub use analyze::*;
+pub use editor::*;
 pub use run::*;
+pub use session::*;
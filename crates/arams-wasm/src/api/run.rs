@@ -1,32 +1,43 @@
-use arams_core::IntoSourceCode;
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
-use crate::models::{CompilationError, Machine, RunResult, RuntimeError};
+use crate::{
+    conversions::source_code_from_js,
+    models::{
+        CompilationError, CompilationErrors, Dialect, Machine, RunOptions, RunResult, RuntimeError,
+        StopReason, TraceEntry,
+    },
+};
 
+// `options` may be omitted, see `RunOptions` for the defaults
 #[wasm_bindgen]
-pub fn run(input: JsValue, registers: JsValue) -> Result<JsValue, JsError> {
-    let source_code = if input.is_string() {
-        serde_wasm_bindgen::from_value::<String>(input)?.into_lines()
-    } else if input.is_array() {
-        serde_wasm_bindgen::from_value(input)?
+pub fn run(input: JsValue, registers: JsValue, options: JsValue) -> Result<JsValue, JsError> {
+    let source_code = source_code_from_js(input)?;
+
+    let registers: HashMap<usize, u64> = serde_wasm_bindgen::from_value(registers)?;
+    let options: RunOptions = if options.is_undefined() || options.is_null() {
+        RunOptions::default()
     } else {
-        return Err(JsError::new(
-            "Input must be a string or an array of strings",
-        ));
+        serde_wasm_bindgen::from_value(options)?
     };
 
-    let registers: HashMap<usize, u64> = serde_wasm_bindgen::from_value(registers)?;
+    let compiled = match options.dialect {
+        Dialect::Arams => arams_core::compile(source_code).map(|program| {
+            let registers = program.initial_registers(&registers);
+            (program, registers)
+        }),
+        // the register initialisations of the Remasp file are defaults like `// @input` directives
+        Dialect::Remasp => {
+            arams_core::remasp::import(source_code).map(|(program, mut defaults)| {
+                defaults.extend(program.initial_registers(&registers));
+                (program, defaults)
+            })
+        }
+    };
 
-    match arams_core::compile(source_code) {
-        Ok(program) => match arams_core::execute(program, Some(registers)) {
-            Ok(machine) => {
-                let result = Machine {
-                    registers: machine.get_registers().clone(),
-                    accumulator: machine.get_accumulator(),
-                };
-                Ok(serde_wasm_bindgen::to_value(&RunResult::Ok(result))?)
-            }
+    match compiled {
+        Ok((program, registers)) => match execute_with_options(&program, registers, &options) {
+            Ok(machine) => Ok(serde_wasm_bindgen::to_value(&RunResult::Ok(machine))?),
             Err(err) => Ok(serde_wasm_bindgen::to_value(&RunResult::ExecutionError(
                 RuntimeError::from(err),
             ))?),
@@ -35,8 +46,67 @@ pub fn run(input: JsValue, registers: JsValue) -> Result<JsValue, JsError> {
             let errors: Vec<CompilationError> = errors.into_iter().map(Into::into).collect();
 
             Ok(serde_wasm_bindgen::to_value(&RunResult::CompilationError(
-                errors,
+                CompilationErrors { errors },
             ))?)
         }
     }
 }
+
+fn execute_with_options(
+    program: &arams_core::Program,
+    registers: HashMap<usize, u64>,
+    options: &RunOptions,
+) -> Result<Machine, arams_core::RuntimeError> {
+    let mut machine = arams_core::Machine::new_with_preseeded_registers(registers);
+    machine.set_arithmetic_mode(options.arithmetic);
+    machine.set_strict_reads(options.strict_reads);
+
+    let mut trace = options.trace.then(Vec::new);
+    while !machine.is_stopped() {
+        if options
+            .max_steps
+            .is_some_and(|max_steps| machine.get_steps() >= max_steps)
+        {
+            return Ok(Machine {
+                stop_reason: Some(StopReason::StepLimit),
+                trace,
+                ..Machine::from(machine)
+            });
+        }
+
+        let program_counter = machine.get_program_counter();
+        arams_core::step(program, &mut machine)?;
+
+        if let Some(trace) = &mut trace {
+            trace.push(TraceEntry {
+                step: machine.get_steps(),
+                program_counter,
+                line: program.source_line(program_counter),
+                instruction: program
+                    .fetch(program_counter)
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+                accumulator: machine.get_accumulator(),
+            });
+        }
+    }
+
+    Ok(Machine {
+        trace,
+        ..Machine::from(machine)
+    })
+}
+
+#[wasm_bindgen]
+pub fn resume(snapshot: &str) -> Result<JsValue, JsError> {
+    let snapshot: arams_core::Snapshot = serde_json::from_str(snapshot)?;
+
+    match snapshot.resume() {
+        Ok(machine) => Ok(serde_wasm_bindgen::to_value(&RunResult::Ok(
+            Machine::from(machine),
+        ))?),
+        Err(err) => Ok(serde_wasm_bindgen::to_value(&RunResult::ExecutionError(
+            RuntimeError::from(err),
+        ))?),
+    }
+}
@@ -0,0 +1,329 @@
+use arams_core::{Machine, Program};
+use std::collections::{HashMap, HashSet, VecDeque};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    api::editor::{hover_with_machine, inlay_hints_with_machine},
+    conversions::source_code_from_js,
+    models::{
+        ChunkResult, ChunkStatus, CompilationError, RuntimeError, SessionState, SessionStatus,
+        StopReason,
+    },
+};
+
+// `stepBack` can undo at most this many steps, older states are dropped
+const HISTORY_LIMIT: usize = 10_000;
+
+// `continue` pauses after this many steps, so an endless loop does not freeze the page
+const CONTINUE_LIMIT: u32 = 100_000;
+
+#[wasm_bindgen]
+pub struct Session {
+    program: Program,
+    initial_registers: HashMap<usize, u64>,
+    machine: Machine,
+    history: VecDeque<Machine>,
+    breakpoints: HashSet<usize>,
+    error: Option<RuntimeError>,
+}
+
+#[wasm_bindgen]
+impl Session {
+    // throws the compilation errors if the program does not compile
+    #[wasm_bindgen(constructor)]
+    pub fn new(input: JsValue, registers: JsValue) -> Result<Session, JsValue> {
+        let source_code = source_code_from_js(input)?;
+        let registers: HashMap<usize, u64> = if registers.is_undefined() || registers.is_null() {
+            HashMap::new()
+        } else {
+            serde_wasm_bindgen::from_value(registers)?
+        };
+
+        let program = arams_core::compile(source_code).map_err(|errors| {
+            let errors: Vec<CompilationError> = errors.into_iter().map(Into::into).collect();
+            serde_wasm_bindgen::to_value(&errors).unwrap_or_else(JsValue::from)
+        })?;
+
+        Ok(Self::from_program(program, &registers))
+    }
+
+    // executes the next instruction
+    pub fn step(&mut self) -> Result<JsValue, JsError> {
+        self.advance(true);
+        self.get_state()
+    }
+
+    // restores the state before the last step
+    #[wasm_bindgen(js_name = "stepBack")]
+    pub fn step_back(&mut self) -> Result<JsValue, JsError> {
+        self.undo();
+        self.get_state()
+    }
+
+    // executes instructions until a breakpoint is reached, the program stops or
+    // `CONTINUE_LIMIT` steps have been executed, the status is then still `running`
+    #[wasm_bindgen(js_name = "continue")]
+    pub fn continue_execution(&mut self) -> Result<JsValue, JsError> {
+        self.run(CONTINUE_LIMIT, true);
+        self.get_state()
+    }
+
+    // executes at most `max_steps` instructions, so long running programs do not block the browser,
+    // `stepBack` undoes the whole chunk instead of cloning the machine for every step
+    #[wasm_bindgen(js_name = "runSteps")]
+    pub fn run_steps(&mut self, max_steps: u32) -> Result<JsValue, JsError> {
+        let status = self.run_chunk(max_steps);
+
+        Ok(serde_wasm_bindgen::to_value(&ChunkResult {
+            status,
+            state: self.state(),
+        })?)
+    }
+
+    // breakpoints are source line numbers, they replace all previous breakpoints
+    #[wasm_bindgen(js_name = "setBreakpoints")]
+    pub fn set_breakpoints(&mut self, lines: JsValue) -> Result<(), JsError> {
+        let lines: Vec<usize> = serde_wasm_bindgen::from_value(lines)?;
+        self.breakpoints = lines.into_iter().collect();
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = "getState")]
+    pub fn get_state(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.state())?)
+    }
+
+    // like `hover`, but with the current values of the registers under the cursor
+    pub fn hover(&self, input: JsValue, line: usize, character: usize) -> Result<JsValue, JsError> {
+        hover_with_machine(input, line, character, Some(&self.machine))
+    }
+
+    // like `inlayHints`, but with the addresses indirect operands currently resolve to
+    #[wasm_bindgen(js_name = "inlayHints")]
+    pub fn inlay_hints(&self, input: JsValue) -> Result<JsValue, JsError> {
+        inlay_hints_with_machine(input, Some(&self.machine))
+    }
+
+    // restarts the program with the initial registers, breakpoints are kept
+    pub fn reset(&mut self) -> Result<JsValue, JsError> {
+        self.machine = Machine::new_with_preseeded_registers(self.initial_registers.clone());
+        self.history.clear();
+        self.error = None;
+        self.get_state()
+    }
+}
+
+impl Session {
+    fn from_program(program: Program, registers: &HashMap<usize, u64>) -> Self {
+        let initial_registers = program.initial_registers(registers);
+
+        Self {
+            machine: Machine::new_with_preseeded_registers(initial_registers.clone()),
+            program,
+            initial_registers,
+            history: VecDeque::new(),
+            breakpoints: HashSet::new(),
+            error: None,
+        }
+    }
+
+    fn can_advance(&self) -> bool {
+        !self.machine.is_stopped() && self.error.is_none()
+    }
+
+    // returns whether an instruction has been executed and the program can continue, `record`
+    // saves the state before the instruction for `stepBack`
+    fn advance(&mut self, record: bool) -> bool {
+        if !self.can_advance() {
+            return false;
+        }
+        if record {
+            self.record();
+        }
+
+        if let Err(err) = arams_core::step(&self.program, &mut self.machine) {
+            self.error = Some(RuntimeError::from(err));
+            return false;
+        }
+
+        !self.machine.is_stopped()
+    }
+
+    // stops before an instruction on a breakpoint line, the first instruction is always executed
+    // so a paused execution can be continued
+    fn run(&mut self, max_steps: u32, record: bool) -> ChunkStatus {
+        for _ in 0..max_steps {
+            if !self.advance(record) {
+                return ChunkStatus::Finished;
+            }
+            if self.at_breakpoint() {
+                return ChunkStatus::Paused;
+            }
+        }
+
+        if self.can_advance() {
+            ChunkStatus::Running
+        } else {
+            ChunkStatus::Finished
+        }
+    }
+
+    fn run_chunk(&mut self, max_steps: u32) -> ChunkStatus {
+        if max_steps > 0 && self.can_advance() {
+            self.record();
+        }
+        self.run(max_steps, false)
+    }
+
+    fn undo(&mut self) {
+        if let Some(machine) = self.history.pop_back() {
+            self.machine = machine;
+            self.error = None;
+        }
+    }
+
+    fn record(&mut self) {
+        if self.history.len() == HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.machine.clone());
+    }
+
+    fn state(&self) -> SessionState {
+        let status = if self.error.is_some() {
+            SessionStatus::Error
+        } else if self.machine.is_stopped() {
+            SessionStatus::Stopped
+        } else {
+            SessionStatus::Running
+        };
+        let program_counter = self.machine.get_program_counter();
+
+        SessionState {
+            status,
+            program_counter,
+            line: self.program.source_line(program_counter),
+            accumulator: self.machine.get_accumulator(),
+            registers: self.machine.get_registers().clone(),
+            steps: self.machine.get_steps(),
+            stop_reason: self.machine.get_stop_reason().map(StopReason::from),
+            error: self.error.clone(),
+            can_step_back: !self.history.is_empty(),
+        }
+    }
+
+    fn at_breakpoint(&self) -> bool {
+        self.program
+            .source_line(self.machine.get_program_counter())
+            .is_some_and(|line| self.breakpoints.contains(&line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTDOWN: &str = "load 1
+        loop: sub #1
+        store 1
+        jnzero loop
+        end";
+
+    fn session(source_code: &str, registers: &[(usize, u64)]) -> Session {
+        let program = arams_core::compile(source_code).unwrap();
+        Session::from_program(program, &registers.iter().copied().collect())
+    }
+
+    #[test]
+    fn steps_can_be_undone() {
+        let mut session = session(COUNTDOWN, &[(1, 2)]);
+        session.undo();
+        assert_eq!(session.machine.get_steps(), 0);
+
+        assert!(session.advance(true));
+        assert!(session.advance(true));
+        assert_eq!(session.machine.get_accumulator(), 1);
+        assert_eq!(session.history.len(), 2);
+
+        session.undo();
+        assert_eq!(session.machine.get_accumulator(), 2);
+        assert_eq!(session.machine.get_steps(), 1);
+        assert!(session.state().can_step_back);
+
+        while session.advance(true) {}
+        assert!(matches!(session.state().status, SessionStatus::Stopped));
+        assert_eq!(session.machine.get_register_value(1), &0);
+        assert_eq!(session.history.len() as u64, session.machine.get_steps());
+        assert!(!session.advance(true));
+    }
+
+    #[test]
+    fn undoing_a_runtime_error_clears_it() {
+        let mut session = session("load #4\ngoto missing\nend", &[]);
+
+        assert!(session.advance(true));
+        assert!(!session.advance(true));
+        assert!(matches!(session.state().status, SessionStatus::Error));
+        assert!(!session.advance(true));
+
+        session.undo();
+        assert!(session.error.is_none());
+        assert!(matches!(session.state().status, SessionStatus::Running));
+        assert_eq!(session.state().line, Some(2));
+    }
+
+    #[test]
+    fn execution_pauses_at_breakpoints() {
+        let mut session = session(COUNTDOWN, &[(1, 3)]);
+        session.breakpoints = HashSet::from([4]);
+
+        assert!(matches!(
+            session.run(CONTINUE_LIMIT, true),
+            ChunkStatus::Paused
+        ));
+        assert_eq!(session.state().line, Some(4));
+        assert_eq!(session.machine.get_steps(), 3);
+
+        // a paused execution continues past the breakpoint it stopped at
+        assert!(matches!(
+            session.run(CONTINUE_LIMIT, true),
+            ChunkStatus::Paused
+        ));
+        assert_eq!(session.machine.get_steps(), 6);
+
+        session.breakpoints.clear();
+        assert!(matches!(
+            session.run(CONTINUE_LIMIT, true),
+            ChunkStatus::Finished
+        ));
+        assert!(matches!(session.state().status, SessionStatus::Stopped));
+    }
+
+    #[test]
+    fn continue_stops_endless_loops() {
+        let mut session = session("loop: goto loop", &[]);
+
+        assert!(matches!(
+            session.run(CONTINUE_LIMIT, true),
+            ChunkStatus::Running
+        ));
+        assert_eq!(session.machine.get_steps(), u64::from(CONTINUE_LIMIT));
+        assert_eq!(session.history.len(), HISTORY_LIMIT);
+    }
+
+    #[test]
+    fn chunks_are_undone_at_once() {
+        let mut session = session("loop: goto loop", &[]);
+
+        assert!(matches!(session.run_chunk(0), ChunkStatus::Running));
+        assert!(session.history.is_empty());
+
+        assert!(matches!(session.run_chunk(1000), ChunkStatus::Running));
+        assert!(matches!(session.run_chunk(1000), ChunkStatus::Running));
+        assert_eq!(session.machine.get_steps(), 2000);
+        assert_eq!(session.history.len(), 2);
+
+        session.undo();
+        assert_eq!(session.machine.get_steps(), 1000);
+    }
+}
@@ -1,4 +1,11 @@
-use crate::models::{CompilationError, RuntimeError};
+use arams_core::IntoSourceCode;
+use wasm_bindgen::prelude::*;
+
+use crate::models::{
+    CodeAction, CompilationError, CompletionItem, CompletionKind, Diagnostic, DiagnosticSeverity,
+    DocumentSymbol, FoldingRange, FoldingRangeKind, Hover, InlayHint, Machine, Position, Range,
+    RuntimeError, StopReason, TextEdit,
+};
 
 impl From<arams_core::CompileError> for CompilationError {
     fn from(err: arams_core::CompileError) -> Self {
@@ -12,7 +19,152 @@ impl From<arams_core::CompileError> for CompilationError {
 impl From<arams_core::RuntimeError> for RuntimeError {
     fn from(err: arams_core::RuntimeError) -> Self {
         Self {
+            line: err.line_number(),
             message: err.to_string(),
         }
     }
 }
+
+impl From<arams_core::Machine> for Machine {
+    fn from(machine: arams_core::Machine) -> Self {
+        Self {
+            registers: machine.get_registers().clone(),
+            accumulator: machine.get_accumulator(),
+            steps: machine.get_steps(),
+            stop_reason: machine.get_stop_reason().map(StopReason::from),
+            trace: None,
+        }
+    }
+}
+
+impl From<arams_core::StopReason> for StopReason {
+    fn from(reason: arams_core::StopReason) -> Self {
+        match reason {
+            arams_core::StopReason::End => StopReason::End,
+            arams_core::StopReason::EndOfProgram => StopReason::EndOfProgram,
+            arams_core::StopReason::DivisionByZero => StopReason::DivisionByZero,
+        }
+    }
+}
+
+impl From<arams_core::lsp::Position> for Position {
+    fn from(position: arams_core::lsp::Position) -> Self {
+        Self {
+            line: position.line(),
+            character: position.character(),
+        }
+    }
+}
+
+impl From<arams_core::lsp::Range> for Range {
+    fn from(range: arams_core::lsp::Range) -> Self {
+        Self {
+            start: range.start().into(),
+            end: range.end().into(),
+        }
+    }
+}
+
+impl From<Range> for arams_core::lsp::Range {
+    fn from(range: Range) -> Self {
+        Self::new(
+            arams_core::lsp::Position::new(range.start.line, range.start.character),
+            arams_core::lsp::Position::new(range.end.line, range.end.character),
+        )
+    }
+}
+
+impl From<&arams_core::lsp::Diagnostic> for Diagnostic {
+    fn from(diagnostic: &arams_core::lsp::Diagnostic) -> Self {
+        Self {
+            range: diagnostic.range().into(),
+            severity: match diagnostic.severity() {
+                arams_core::lsp::DiagnosticSeverity::Error => DiagnosticSeverity::Error,
+                arams_core::lsp::DiagnosticSeverity::Warning => DiagnosticSeverity::Warning,
+            },
+            message: diagnostic.message(),
+        }
+    }
+}
+
+impl From<&arams_core::lsp::Completion> for CompletionItem {
+    fn from(completion: &arams_core::lsp::Completion) -> Self {
+        Self {
+            label: completion.label().to_string(),
+            kind: match completion.kind() {
+                arams_core::lsp::CompletionKind::Keyword => CompletionKind::Keyword,
+                arams_core::lsp::CompletionKind::Label => CompletionKind::Label,
+            },
+            detail: completion.detail().to_string(),
+            documentation: completion.documentation().to_string(),
+        }
+    }
+}
+
+impl From<&arams_core::lsp::CodeAction> for CodeAction {
+    fn from(action: &arams_core::lsp::CodeAction) -> Self {
+        Self {
+            title: action.title().to_string(),
+            edit: TextEdit {
+                range: action.edit().range().into(),
+                text: action.edit().text().to_string(),
+            },
+            diagnostic: action.diagnostic().into(),
+        }
+    }
+}
+
+impl From<arams_core::lsp::Hover> for Hover {
+    fn from(hover: arams_core::lsp::Hover) -> Self {
+        Self {
+            range: hover.range().into(),
+            contents: hover.contents().to_string(),
+        }
+    }
+}
+
+impl From<&arams_core::lsp::InlayHint> for InlayHint {
+    fn from(hint: &arams_core::lsp::InlayHint) -> Self {
+        Self {
+            position: hint.position().into(),
+            label: hint.label().to_string(),
+            tooltip: hint.tooltip().to_string(),
+        }
+    }
+}
+
+impl From<&arams_core::lsp::DocumentSymbol> for DocumentSymbol {
+    fn from(symbol: &arams_core::lsp::DocumentSymbol) -> Self {
+        Self {
+            name: symbol.name().to_string(),
+            detail: symbol.detail().to_string(),
+            range: symbol.range().into(),
+            selection_range: symbol.selection_range().into(),
+        }
+    }
+}
+
+impl From<&arams_core::lsp::FoldingRange> for FoldingRange {
+    fn from(range: &arams_core::lsp::FoldingRange) -> Self {
+        Self {
+            start_line: range.start_line(),
+            end_line: range.end_line(),
+            kind: match range.kind() {
+                arams_core::lsp::FoldingRangeKind::Region => FoldingRangeKind::Region,
+                arams_core::lsp::FoldingRangeKind::Comment => FoldingRangeKind::Comment,
+            },
+        }
+    }
+}
+
+pub(crate) fn source_code_from_js(input: JsValue) -> Result<Vec<String>, JsError> {
+    if input.is_string() {
+        Ok(serde_wasm_bindgen::from_value::<String>(input)?.into_lines())
+    } else if input.is_array() {
+        Ok(serde_wasm_bindgen::from_value(input)?)
+    } else {
+        Err(JsError::new(
+            "Input must be a string or an array of strings",
+        ))
+    }
+}
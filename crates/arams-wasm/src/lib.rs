@@ -1,5 +1,6 @@
 mod api;
 mod conversions;
 mod models;
+pub mod typings;
 
 pub use api::*;
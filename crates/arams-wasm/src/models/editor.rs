@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::typings::typescript;
+
+typescript! {
+    // zero-based, characters are counted in UTF-16 code units
+    #[derive(Serialize, Deserialize)]
+    pub struct Position {
+        pub line: usize,
+        pub character: usize,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct Range {
+        pub start: Position,
+        pub end: Position,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum DiagnosticSeverity {
+        Error,
+        Warning,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct Diagnostic {
+        pub range: Range,
+        pub severity: DiagnosticSeverity,
+        pub message: String,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum CompletionKind {
+        Keyword,
+        Label,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct CompletionItem {
+        pub label: String,
+        pub kind: CompletionKind,
+        pub detail: String,
+        pub documentation: String,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct TextEdit {
+        pub range: Range,
+        pub text: String,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct CodeAction {
+        pub title: String,
+        pub edit: TextEdit,
+        pub diagnostic: Diagnostic,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct Hover {
+        pub range: Range,
+        pub contents: String,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct InlayHint {
+        pub position: Position,
+        pub label: String,
+        pub tooltip: String,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct DocumentSymbol {
+        pub name: String,
+        pub detail: String,
+        pub range: Range,
+        pub selection_range: Range,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum FoldingRangeKind {
+        Region,
+        Comment,
+    }
+}
+
+typescript! {
+    // whole lines, the end line is included
+    #[derive(Serialize, Deserialize)]
+    pub struct FoldingRange {
+        pub start_line: usize,
+        pub end_line: usize,
+        pub kind: FoldingRangeKind,
+    }
+}
+
+typescript! {
+    // the names of the types and modifiers encoded by `semanticTokens`
+    #[derive(Serialize, Deserialize)]
+    pub struct SemanticTokensLegend {
+        pub token_types: Vec<String>,
+        pub token_modifiers: Vec<String>,
+    }
+}
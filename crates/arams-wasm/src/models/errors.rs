@@ -1,12 +1,26 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
-pub struct CompilationError {
-    pub line: usize,
-    pub message: String,
+use crate::typings::typescript;
+
+typescript! {
+    #[derive(Default, Serialize, Deserialize)]
+    pub struct CompilationError {
+        pub line: usize,
+        pub message: String,
+    }
+}
+
+typescript! {
+    #[derive(Default, Serialize, Deserialize)]
+    pub struct CompilationErrors {
+        pub errors: Vec<CompilationError>,
+    }
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct RuntimeError {
-    pub message: String,
+typescript! {
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    pub struct RuntimeError {
+        pub line: Option<usize>,
+        pub message: String,
+    }
 }
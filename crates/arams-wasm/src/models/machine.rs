@@ -1,8 +1,39 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Serialize, Deserialize)]
-pub struct Machine {
-    pub registers: HashMap<usize, u64>,
-    pub accumulator: u64,
+use crate::typings::typescript;
+
+typescript! {
+    #[derive(Clone, Copy, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum StopReason {
+        End,
+        EndOfProgram,
+        DivisionByZero,
+        // the `max_steps` option has been reached before the program stopped
+        StepLimit,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct TraceEntry {
+        pub step: u64,
+        pub program_counter: usize,
+        pub line: Option<usize>,
+        pub instruction: String,
+        pub accumulator: u64,
+    }
+}
+
+typescript! {
+    #[derive(Default, Serialize, Deserialize)]
+    pub struct Machine {
+        pub registers: HashMap<usize, u64>,
+        pub accumulator: u64,
+        pub steps: u64,
+        pub stop_reason: Option<StopReason>,
+        // `null` unless the `trace` option is set
+        pub trace: Option<Vec<TraceEntry>>,
+    }
 }
@@ -1,7 +1,13 @@
+pub mod editor;
 pub mod errors;
 pub mod machine;
+pub mod options;
 pub mod results;
+pub mod session;
 
+pub use editor::*;
 pub use errors::*;
 pub use machine::*;
+pub use options::*;
 pub use results::*;
+pub use session::*;
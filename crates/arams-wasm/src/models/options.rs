@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+use crate::typings::typescript;
+
+typescript! {
+    #[derive(Clone, Copy, Default, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Dialect {
+        #[default]
+        Arams,
+        // source files of the Remasp GUI, see docs/remasp.md
+        Remasp,
+    }
+}
+
+typescript! {
+    @partial
+    #[derive(Default, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct RunOptions {
+        pub max_steps: Option<u64>,
+        pub arithmetic: arams_core::ArithmeticMode,
+        pub dialect: Dialect,
+        pub trace: bool,
+        pub strict_reads: bool,
+    }
+}
@@ -1,14 +1,18 @@
 use serde::{Deserialize, Serialize};
 
-use super::{CompilationError, Machine, RuntimeError};
+use super::{CompilationErrors, Machine, RuntimeError};
+use crate::typings::typescript;
 
-#[derive(Serialize, Deserialize)]
-#[serde(tag = "status", rename_all = "lowercase")]
-pub enum RunResult {
-    #[serde(rename = "machine")]
-    Ok(Machine),
-    #[serde(rename = "compilation_error")]
-    CompilationError(Vec<CompilationError>),
-    #[serde(rename = "execution_error")]
-    ExecutionError(RuntimeError),
+typescript! {
+    // internally tagged enums cannot contain sequences, so the compilation errors are wrapped
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "status", rename_all = "lowercase")]
+    pub enum RunResult {
+        #[serde(rename = "machine")]
+        Ok(Machine),
+        #[serde(rename = "compilation_error")]
+        CompilationError(CompilationErrors),
+        #[serde(rename = "execution_error")]
+        ExecutionError(RuntimeError),
+    }
 }
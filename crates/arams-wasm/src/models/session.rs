@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{RuntimeError, StopReason};
+use crate::typings::typescript;
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum SessionStatus {
+        Running,
+        Stopped,
+        Error,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct SessionState {
+        pub status: SessionStatus,
+        pub program_counter: usize,
+        pub line: Option<usize>,
+        pub accumulator: u64,
+        pub registers: HashMap<usize, u64>,
+        pub steps: u64,
+        pub stop_reason: Option<StopReason>,
+        pub error: Option<RuntimeError>,
+        pub can_step_back: bool,
+    }
+}
+
+typescript! {
+    // the result of `runSteps`, `running` means that the step budget is used up
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum ChunkStatus {
+        Paused,
+        Running,
+        Finished,
+    }
+}
+
+typescript! {
+    #[derive(Serialize, Deserialize)]
+    pub struct ChunkResult {
+        pub status: ChunkStatus,
+        pub state: SessionState,
+    }
+}
@@ -0,0 +1,310 @@
+use arams_core::{ArithmeticMode, lsp::LSPTokenKind};
+use std::collections::HashMap;
+
+use crate::{
+    api::AnalyzeToken,
+    models::{
+        ChunkResult, ChunkStatus, CodeAction, CompilationError, CompilationErrors, CompletionItem,
+        CompletionKind, Diagnostic, DiagnosticSeverity, Dialect, DocumentSymbol, FoldingRange,
+        FoldingRangeKind, Hover, InlayHint, Machine, Position, Range, RunOptions, RunResult,
+        RuntimeError, SemanticTokensLegend, SessionState, SessionStatus, StopReason, TextEdit,
+        TraceEntry,
+    },
+};
+
+// marks the part of a `.d.ts` file that is replaced with the generated declarations
+pub const GENERATED_BEGIN: &str = "// @generated begin";
+pub const GENERATED_END: &str = "// @generated end";
+
+pub trait TypeScript {
+    // the type as used in other declarations, e.g. `number` or `Machine`
+    fn ts_type() -> String;
+
+    fn ts_declaration() -> Option<String> {
+        None
+    }
+}
+
+// all model types in the order they are declared in `arams.d.ts`
+pub fn declarations() -> String {
+    [
+        CompilationError::ts_declaration(),
+        CompilationErrors::ts_declaration(),
+        RuntimeError::ts_declaration(),
+        StopReason::ts_declaration(),
+        TraceEntry::ts_declaration(),
+        Machine::ts_declaration(),
+        RunResult::ts_declaration(),
+        ArithmeticMode::ts_declaration(),
+        Dialect::ts_declaration(),
+        RunOptions::ts_declaration(),
+        LSPTokenKind::ts_declaration(),
+        AnalyzeToken::ts_declaration(),
+        SessionStatus::ts_declaration(),
+        SessionState::ts_declaration(),
+        ChunkStatus::ts_declaration(),
+        ChunkResult::ts_declaration(),
+        Position::ts_declaration(),
+        Range::ts_declaration(),
+        DiagnosticSeverity::ts_declaration(),
+        Diagnostic::ts_declaration(),
+        CompletionKind::ts_declaration(),
+        CompletionItem::ts_declaration(),
+        TextEdit::ts_declaration(),
+        CodeAction::ts_declaration(),
+        Hover::ts_declaration(),
+        InlayHint::ts_declaration(),
+        DocumentSymbol::ts_declaration(),
+        FoldingRangeKind::ts_declaration(),
+        FoldingRange::ts_declaration(),
+        SemanticTokensLegend::ts_declaration(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+// replaces everything between the generated markers, keeping the indentation of the begin marker
+pub fn update(contents: &str, declarations: &str) -> Option<String> {
+    let begin = contents.find(GENERATED_BEGIN)?;
+    let end = contents[begin..].find(GENERATED_END)? + begin;
+    let line_start = contents[..begin].rfind('\n').map_or(0, |index| index + 1);
+    let indentation = &contents[line_start..begin];
+
+    let declarations: String = declarations
+        .lines()
+        .map(|line| match line {
+            "" => "\n".to_string(),
+            line => format!("{}{}\n", indentation, line),
+        })
+        .collect();
+
+    Some(format!(
+        "{}{}\n{}{}{}",
+        &contents[..begin],
+        GENERATED_BEGIN,
+        declarations,
+        indentation,
+        &contents[end..]
+    ))
+}
+
+pub(crate) fn field_type<T: TypeScript>(ts_type: Option<&str>) -> String {
+    ts_type.map_or_else(T::ts_type, str::to_string)
+}
+
+pub(crate) fn object_declaration(name: &str, fields: &[String]) -> String {
+    let mut declaration = format!("export type {} = {{\n", name);
+    for field in fields {
+        declaration.push_str(&format!("    {}\n", field));
+    }
+    declaration.push_str("};\n");
+    declaration
+}
+
+pub(crate) fn union_declaration(name: &str, variants: &[String]) -> String {
+    let single_line = format!("export type {} = {};\n", name, variants.join(" | "));
+    if single_line.len() <= 100 {
+        return single_line;
+    }
+
+    let mut declaration = format!("export type {} =\n", name);
+    for variant in variants {
+        declaration.push_str(&format!("    | {}\n", variant));
+    }
+    declaration.pop();
+    declaration.push_str(";\n");
+    declaration
+}
+
+// defines a model type together with its TypeScript declaration:
+// - structs become object types, `@partial` makes all fields optional for `#[serde(default)]` structs
+// - enums with unit variants become unions of the strings serde serializes them to
+// - enums with `#[serde(tag = "...")]` become unions of their newtype variants and the tag
+// the TypeScript type of a field can be overridden with `field: Type as "TsType"`
+macro_rules! typescript {
+    (
+        #[derive($($derive:tt)*)]
+        #[serde(tag = $tag:literal $(, $($serde:tt)*)?)]
+        $(#[$attr:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_attr:meta])* $variant:ident($inner:ty)),* $(,)?
+        }
+    ) => {
+        #[derive($($derive)*)]
+        #[serde(tag = $tag $(, $($serde)*)?)]
+        $(#[$attr])*
+        pub enum $name {
+            $($(#[$variant_attr])* $variant($inner)),*
+        }
+
+        impl $crate::typings::TypeScript for $name {
+            fn ts_type() -> String {
+                stringify!($name).to_string()
+            }
+
+            fn ts_declaration() -> Option<String> {
+                let variants: Vec<String> = vec![$({
+                    let value = serde_json::to_value($name::$variant(<$inner>::default()))
+                        .expect("tagged variants serialize to objects");
+                    format!(
+                        "({{ {}: {} }} & {})",
+                        $tag,
+                        value[$tag],
+                        <$inner as $crate::typings::TypeScript>::ts_type()
+                    )
+                }),*];
+                Some($crate::typings::union_declaration(stringify!($name), &variants))
+            }
+        }
+    };
+    (
+        $(#[$attr:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_attr:meta])* $variant:ident),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        pub enum $name {
+            $($(#[$variant_attr])* $variant),*
+        }
+
+        impl $crate::typings::TypeScript for $name {
+            fn ts_type() -> String {
+                stringify!($name).to_string()
+            }
+
+            fn ts_declaration() -> Option<String> {
+                let variants: Vec<String> = vec![$(
+                    serde_json::to_string(&$name::$variant).expect("unit variants serialize to strings")
+                ),*];
+                Some($crate::typings::union_declaration(stringify!($name), &variants))
+            }
+        }
+    };
+    (
+        $(@$partial:ident)?
+        $(#[$attr:meta])*
+        pub struct $name:ident {
+            $($(#[$field_attr:meta])* pub $field:ident: $type:ty $(as $ts_type:literal)?),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        pub struct $name {
+            $($(#[$field_attr])* pub $field: $type),*
+        }
+
+        impl $crate::typings::TypeScript for $name {
+            fn ts_type() -> String {
+                stringify!($name).to_string()
+            }
+
+            fn ts_declaration() -> Option<String> {
+                let partial: &[&str] = &[$(stringify!($partial)),*];
+                let optional = if partial.is_empty() { "" } else { "?" };
+                let fields: Vec<String> = vec![$(format!(
+                    "{}{}: {};",
+                    stringify!($field),
+                    optional,
+                    $crate::typings::field_type::<$type>(None $(.or(Some($ts_type)))?)
+                )),*];
+                Some($crate::typings::object_declaration(stringify!($name), &fields))
+            }
+        }
+    };
+}
+
+pub(crate) use typescript;
+
+// enums of `arams_core`, every variant has to be listed, otherwise the `match` does not compile
+macro_rules! foreign_typescript {
+    ($name:ident, $to_string:expr, [$($variant:ident),* $(,)?]) => {
+        impl $crate::typings::TypeScript for $name {
+            fn ts_type() -> String {
+                stringify!($name).to_string()
+            }
+
+            fn ts_declaration() -> Option<String> {
+                let to_string: fn(&$name) -> String = $to_string;
+                let variants: Vec<String> = [$($name::$variant),*]
+                    .iter()
+                    .map(|variant| match variant {
+                        $($name::$variant => format!("\"{}\"", to_string(variant))),*
+                    })
+                    .collect();
+                Some($crate::typings::union_declaration(stringify!($name), &variants))
+            }
+        }
+    };
+}
+
+foreign_typescript!(
+    ArithmeticMode,
+    |mode| serde_json::to_value(mode)
+        .ok()
+        .and_then(|mode| mode.as_str().map(str::to_string))
+        .unwrap_or_default(),
+    [Wrapping, Saturating, Checked]
+);
+
+foreign_typescript!(
+    LSPTokenKind,
+    |kind| kind.to_string(),
+    [
+        Load,
+        Store,
+        Add,
+        Sub,
+        Mul,
+        Div,
+        Goto,
+        Jzero,
+        Jnzero,
+        End,
+        LabelDefinition,
+        JumpArgument,
+        ImmediateArgument,
+        IndirectAddressArgument,
+        DirectAddressArgument,
+        Comment,
+        NewLine,
+        Unknown,
+    ]
+);
+
+macro_rules! primitive_typescript {
+    ($ts_type:literal: $($type:ty),*) => {
+        $(impl TypeScript for $type {
+            fn ts_type() -> String {
+                $ts_type.to_string()
+            }
+        })*
+    };
+}
+
+primitive_typescript!("number": u32, u64, usize);
+primitive_typescript!("string": String);
+primitive_typescript!("boolean": bool);
+
+impl<T: TypeScript> TypeScript for Option<T> {
+    fn ts_type() -> String {
+        format!("{} | null", T::ts_type())
+    }
+}
+
+impl<T: TypeScript> TypeScript for Vec<T> {
+    fn ts_type() -> String {
+        match T::ts_type() {
+            ts_type if ts_type.contains(' ') => format!("({})[]", ts_type),
+            ts_type => format!("{}[]", ts_type),
+        }
+    }
+}
+
+// serde_wasm_bindgen serializes maps as `Map` objects
+impl<K: TypeScript, V: TypeScript> TypeScript for HashMap<K, V> {
+    fn ts_type() -> String {
+        format!("Map<{}, {}>", K::ts_type(), V::ts_type())
+    }
+}
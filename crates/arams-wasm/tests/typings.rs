@@ -0,0 +1,27 @@
+use arams_wasm::typings;
+
+// the files containing the generated declarations, relative to this crate
+const TYPINGS_FILES: [&str; 2] = ["arams.d.ts", "../../web/arams.d.ts"];
+
+// run with `UPDATE_TYPINGS=1` to regenerate the declarations
+#[test]
+fn typings_are_up_to_date() {
+    let declarations = typings::declarations();
+
+    for file in TYPINGS_FILES {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(file);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let updated = typings::update(&contents, &declarations)
+            .unwrap_or_else(|| panic!("{} has no generated section", file));
+
+        if std::env::var_os("UPDATE_TYPINGS").is_some() {
+            std::fs::write(&path, updated).unwrap();
+        } else {
+            assert!(
+                contents == updated,
+                "{} is out of date, run `UPDATE_TYPINGS=1 cargo test -p arams-wasm --test typings`",
+                file
+            );
+        }
+    }
+}
@@ -13,24 +13,184 @@ declare module "@chfuchte/arams" {
               message: string;
           }>;
 
+    // @generated begin
+    export type CompilationError = {
+        line: number;
+        message: string;
+    };
+
+    export type CompilationErrors = {
+        errors: CompilationError[];
+    };
+
+    export type RuntimeError = {
+        line: number | null;
+        message: string;
+    };
+
+    export type StopReason = "end" | "end_of_program" | "division_by_zero" | "step_limit";
+
+    export type TraceEntry = {
+        step: number;
+        program_counter: number;
+        line: number | null;
+        instruction: string;
+        accumulator: number;
+    };
+
+    export type Machine = {
+        registers: Map<number, number>;
+        accumulator: number;
+        steps: number;
+        stop_reason: StopReason | null;
+        trace: TraceEntry[] | null;
+    };
+
+    export type RunResult =
+        | ({ status: "machine" } & Machine)
+        | ({ status: "compilation_error" } & CompilationErrors)
+        | ({ status: "execution_error" } & RuntimeError);
+
+    export type ArithmeticMode = "wrapping" | "saturating" | "checked";
+
+    export type Dialect = "arams" | "remasp";
+
+    export type RunOptions = {
+        max_steps?: number | null;
+        arithmetic?: ArithmeticMode;
+        dialect?: Dialect;
+        trace?: boolean;
+        strict_reads?: boolean;
+    };
+
+    export type LSPTokenKind =
+        | "load"
+        | "store"
+        | "add"
+        | "sub"
+        | "mul"
+        | "div"
+        | "goto"
+        | "jzero"
+        | "jnzero"
+        | "end"
+        | "label_definition"
+        | "jump_argument"
+        | "immediate_argument"
+        | "indirect_address_argument"
+        | "direct_address_argument"
+        | "comment"
+        | "newline"
+        | "unknown";
+
+    export type AnalyzeToken = {
+        kind: LSPTokenKind;
+        lexeme: string;
+        errors: string[];
+        about: string;
+    };
+
+    export type SessionStatus = "running" | "stopped" | "error";
+
+    export type SessionState = {
+        status: SessionStatus;
+        program_counter: number;
+        line: number | null;
+        accumulator: number;
+        registers: Map<number, number>;
+        steps: number;
+        stop_reason: StopReason | null;
+        error: RuntimeError | null;
+        can_step_back: boolean;
+    };
+
+    export type ChunkStatus = "paused" | "running" | "finished";
+
+    export type ChunkResult = {
+        status: ChunkStatus;
+        state: SessionState;
+    };
+
+    export type Position = {
+        line: number;
+        character: number;
+    };
+
+    export type Range = {
+        start: Position;
+        end: Position;
+    };
+
+    export type DiagnosticSeverity = "error" | "warning";
+
+    export type Diagnostic = {
+        range: Range;
+        severity: DiagnosticSeverity;
+        message: string;
+    };
+
+    export type CompletionKind = "keyword" | "label";
+
+    export type CompletionItem = {
+        label: string;
+        kind: CompletionKind;
+        detail: string;
+        documentation: string;
+    };
+
+    export type TextEdit = {
+        range: Range;
+        text: string;
+    };
+
+    export type CodeAction = {
+        title: string;
+        edit: TextEdit;
+        diagnostic: Diagnostic;
+    };
+
+    export type Hover = {
+        range: Range;
+        contents: string;
+    };
+
+    export type InlayHint = {
+        position: Position;
+        label: string;
+        tooltip: string;
+    };
+
+    export type DocumentSymbol = {
+        name: string;
+        detail: string;
+        range: Range;
+        selection_range: Range;
+    };
+
+    export type FoldingRangeKind = "region" | "comment";
+
+    export type FoldingRange = {
+        start_line: number;
+        end_line: number;
+        kind: FoldingRangeKind;
+    };
+
+    export type SemanticTokensLegend = {
+        token_types: string[];
+        token_modifiers: string[];
+    };
+    // @generated end
+
     /**
      * Runs the provided source code with the given initial register values.
      * @param input Source code as string or array of strings (lines)
      * @param registers Initial register values as a Map<number, number>
-     * @returns An object containing the final machine state (accumulator and registers) if successful,
-     *          otherwise an array of error messages with line numbers (if compilation errors occur)
-     * @throws Error if runtime errors occur
+     * @param options Step limit, arithmetic mode, dialect, trace recording and strict reads
+     * @returns The final machine state, the compilation errors or the runtime error, distinguished by `status`
      */
     function run(
         input: string | string[],
         registers: Map<number, number>,
-    ):
-        | {
-              accumulator: number;
-              registers: Map<number, number>;
-          }
-        | Array<{
-              line: number;
-              message: string;
-          }>;
+        options?: RunOptions,
+    ): RunResult;
 }
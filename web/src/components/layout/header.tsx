@@ -11,9 +11,11 @@ export const Header: Component = () => {
     const runCode = () => {
         try {
             const result = run(aramsStore.sourcecode, aramsStore.preseedRegisters || new Map());
-            if (Array.isArray(result)) {
-                const errorMessages = result.map((err) => `Line ${err.line + 1}: ${err.message}`).join("\n");
+            if (result.status === "compilation_error") {
+                const errorMessages = result.errors.map((err) => `Line ${err.line}: ${err.message}`).join("\n");
                 alert(`Errors compiling the code:\n${errorMessages}`);
+            } else if (result.status === "execution_error") {
+                alert(`Error running the code: ${result.message}`);
             } else {
                 setAramsStore("machine", {
                     accumulator: result.accumulator,